@@ -14,6 +14,8 @@ pub struct SceneGeometry {
     pub ao: Vec<Vec<u8>>,
     pub segments: Vec<Vec<u8>>,
     pub ground: Vec<Vec<u8>>,
+    pub drips: Vec<(u16, u16)>,
+    pub shelter: Vec<Vec<u8>>,
 }
 
 /// Write Rust source file
@@ -44,6 +46,8 @@ pub fn write_rust(path: &Path, palette: &[(u8, u8, u8)], pixels: &[Vec<u8>], geo
     write_array_u8(&mut f, "BG_AO", &geom.ao);
     write_array_u8(&mut f, "BG_SEGMENTS", &geom.segments);
     write_array_u8(&mut f, "BG_GROUND", &geom.ground);
+    write_array_u8(&mut f, "BG_SHELTER", &geom.shelter);
+    write_points(&mut f, "BG_DRIPS", &geom.drips);
 
     println!("  Generated {}", path.display());
 }
@@ -80,6 +84,8 @@ pub fn write_ts(path: &Path, palette: &[(u8, u8, u8)], pixels: &[Vec<u8>], geom:
     write_ts_array_u8(&mut f, "BG_AO", &geom.ao);
     write_ts_array_u8(&mut f, "BG_SEGMENTS", &geom.segments);
     write_ts_array_u8(&mut f, "BG_GROUND", &geom.ground);
+    write_ts_array_u8(&mut f, "BG_SHELTER", &geom.shelter);
+    write_ts_points(&mut f, "BG_DRIPS", &geom.drips);
 
     println!("  Generated {}", path.display());
 }
@@ -137,3 +143,19 @@ fn write_ts_array_i8<W: Write>(f: &mut W, name: &str, data: &[Vec<i8>]) {
     }
     writeln!(f, "];\n").unwrap();
 }
+
+fn write_points<W: Write>(f: &mut W, name: &str, points: &[(u16, u16)]) {
+    writeln!(f, "pub static {}: [(u16,u16); {}] = [", name, points.len()).unwrap();
+    for (x, y) in points {
+        writeln!(f, "    ({},{}),", x, y).unwrap();
+    }
+    writeln!(f, "];\n").unwrap();
+}
+
+fn write_ts_points<W: Write>(f: &mut W, name: &str, points: &[(u16, u16)]) {
+    writeln!(f, "export const {}: [number, number][] = [", name).unwrap();
+    for (x, y) in points {
+        writeln!(f, "  [{},{}],", x, y).unwrap();
+    }
+    writeln!(f, "];\n").unwrap();
+}
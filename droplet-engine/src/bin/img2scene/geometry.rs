@@ -1,6 +1,6 @@
 // geometry.rs - Compute derived geometry from AI outputs
 //
-// Normals, flow field, ambient occlusion, ground mask.
+// Normals, flow field, ambient occlusion, ground mask, drip points, shelter.
 
 /// Compute ground mask from semantic segmentation
 /// Ground = surfaces where water can flow (excludes sky, trees, etc.)
@@ -162,6 +162,103 @@ pub fn compute_ao(depth: &[Vec<f32>], radius: usize) -> Vec<Vec<u8>> {
     ao
 }
 
+/// Find overhang edges where water drips off
+/// An edge is a near surface sitting directly above a far one (depth drops
+/// sharply going down the column). Returns (x, y) of the near-side pixel,
+/// thinned so neighbouring points are at least `spacing` pixels apart.
+pub fn compute_drip_points(depth: &[Vec<f32>], segments: &[Vec<u8>], spacing: usize) -> Vec<(u16, u16)> {
+    let h = depth.len();
+    let w = depth.get(0).map_or(0, |r| r.len());
+
+    const LOOK_DOWN: usize = 4;      // rows to compare across
+    const EDGE_DROP: f32 = 0.03;     // min depth drop to count as overhang
+    const MAX_POINTS: usize = 256;
+    const SKY: u8 = 2;
+
+    // Collect candidate edges with their strength
+    let mut edges: Vec<(f32, u16, u16)> = Vec::new();
+    for y in 0..h.saturating_sub(LOOK_DOWN) {
+        for x in 0..w {
+            if segments[y][x] == SKY { continue; }
+
+            let drop = depth[y][x] - depth[y + LOOK_DOWN][x];
+            if drop > EDGE_DROP {
+                edges.push((drop, x as u16, y as u16));
+            }
+        }
+    }
+
+    // Strongest edges first, suppress neighbours within `spacing`
+    edges.sort_by(|a, b| b.0.total_cmp(&a.0));
+    let mut points: Vec<(u16, u16)> = Vec::new();
+    for &(_, x, y) in &edges {
+        if points.len() >= MAX_POINTS { break; }
+
+        let near = points.iter().any(|&(px, py)| {
+            (px as i32 - x as i32).unsigned_abs() < spacing as u32
+                && (py as i32 - y as i32).unsigned_abs() < spacing as u32
+        });
+        if !near {
+            points.push((x, y));
+        }
+    }
+
+    // Row-major order for stable output
+    points.sort_by_key(|&(x, y)| (y, x));
+    println!("    Found {} drip points", points.len());
+
+    points
+}
+
+/// Compute rain shelter from overhangs
+/// Walking down each column, an overhang edge (near surface above a far one)
+/// becomes a roof. Farther pixels below it are sheltered until the column
+/// comes back to the roof's depth or `max_rows` is reached.
+/// Returns the roof depth (0-255, 0 = open sky) per pixel.
+pub fn compute_shelter(depth: &[Vec<f32>], segments: &[Vec<u8>], max_rows: usize) -> Vec<Vec<u8>> {
+    let h = depth.len();
+    let w = depth.get(0).map_or(0, |r| r.len());
+    let mut shelter = vec![vec![0u8; w]; h];
+
+    const LOOK_DOWN: usize = 4;
+    const EDGE_DROP: f32 = 0.03;
+    const SKY: u8 = 2;
+
+    let mut count = 0;
+    for x in 0..w {
+        let mut roof = 0.0f32;
+        let mut rows_left = 0;
+
+        for y in 0..h {
+            let d = depth[y][x];
+
+            // Open sky or back in front of the roof - no cover
+            if segments[y][x] == SKY || d >= roof {
+                rows_left = 0;
+            }
+
+            if rows_left > 0 {
+                shelter[y][x] = (roof * 255.0) as u8;
+                rows_left -= 1;
+                count += 1;
+            }
+
+            // New (or nearer) roof starts at this edge
+            if y + LOOK_DOWN < h && segments[y][x] != SKY && d - depth[y + LOOK_DOWN][x] > EDGE_DROP {
+                if rows_left == 0 || d > roof {
+                    roof = d;
+                    rows_left = max_rows;
+                }
+            }
+        }
+    }
+
+    let pct = count as f32 / (w * h).max(1) as f32 * 100.0;
+    println!("    Sheltered: {:.1}% ({} pixels)", pct, count);
+
+    shelter
+}
+
 fn fill_edges<T: Copy>(arr: &mut [Vec<T>], w: usize, h: usize) {
     if h < 2 || w < 2 { return; }
 
@@ -5,7 +5,7 @@
 //   2. Extract color palette, dither to indexed
 //   3. Run MiDaS for depth estimation
 //   4. Run SegFormer for semantic segmentation
-//   5. Compute derived maps (normals, flow, AO, ground)
+//   5. Compute derived maps (normals, flow, AO, ground, drips, shelter)
 //   6. Export to Rust + TypeScript
 //
 // Usage: cargo run --bin img2scene -- <image> [--cols N] [--rows N] [--colors N]
@@ -87,6 +87,14 @@ fn main() {
     println!("  Computing ambient occlusion...");
     let ao = geometry::compute_ao(&depth_f, 3);
 
+    // Derived: Drip points (overhang edges)
+    println!("  Finding drip points...");
+    let drips = geometry::compute_drip_points(&depth_f, &segments, 4);
+
+    // Derived: Rain shelter under overhangs
+    println!("  Computing rain shelter...");
+    let shelter = geometry::compute_shelter(&depth_f, &segments, 40);
+
     // Bundle geometry
     let geom = export::SceneGeometry {
         depth: depth_u8,
@@ -97,6 +105,8 @@ fn main() {
         ao,
         segments,
         ground,
+        drips,
+        shelter,
     };
 
     // Memory estimate
@@ -0,0 +1,116 @@
+// splashsheet - Compile a text splash sprite sheet to the binary format
+//
+// Text format (see splashes/default.txt):
+//   sprite <slot>        start a sprite: crown | left | right | spray
+//                        (several sprites in one slot become variants)
+//   frame                start a frame in the current sprite
+//   <x> <drift> <y> <g>  one cell: offsets in units of s, glyph 0-7
+//   # ...                comment (also after a cell)
+//
+// The output is loaded at runtime with RainWorld::load_splash_sheet.
+//
+// Usage: cargo run --bin splashsheet -- <sheet.txt> <sheet.bin>
+
+use std::env;
+use std::fs;
+
+const MAGIC: &[u8; 4] = b"SPLS";
+const VERSION: u8 = 1;
+const SLOTS: [&str; 4] = ["crown", "left", "right", "spray"];
+
+struct Sprite {
+    slot: u8,
+    frames: Vec<Vec<[u8; 4]>>,
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() < 3 {
+        eprintln!("Usage: {} <sheet.txt> <sheet.bin>", args[0]);
+        std::process::exit(1);
+    }
+
+    let text = fs::read_to_string(&args[1]).expect("Failed to read sprite sheet");
+    let sprites = match parse(&text) {
+        Ok(sprites) => sprites,
+        Err(e) => {
+            eprintln!("{}: {}", args[1], e);
+            std::process::exit(1);
+        }
+    };
+
+    let bytes = encode(&sprites);
+    fs::write(&args[2], &bytes).expect("Failed to write sprite sheet");
+
+    let frames: usize = sprites.iter().map(|s| s.frames.len()).sum();
+    println!("Wrote {} ({} sprites, {} frames, {} bytes)", args[2], sprites.len(), frames, bytes.len());
+}
+
+fn parse(text: &str) -> Result<Vec<Sprite>, String> {
+    let mut sprites: Vec<Sprite> = Vec::new();
+
+    for (n, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() { continue; }
+        let err = |msg: &str| format!("line {}: {}", n + 1, msg);
+
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words[0] {
+            "sprite" => {
+                let name = words.get(1).ok_or_else(|| err("sprite needs a slot"))?;
+                let slot = SLOTS.iter().position(|s| s == name)
+                    .ok_or_else(|| err("unknown slot (crown, left, right, spray)"))?;
+                sprites.push(Sprite { slot: slot as u8, frames: Vec::new() });
+            }
+            "frame" => {
+                let sprite = sprites.last_mut().ok_or_else(|| err("frame before any sprite"))?;
+                sprite.frames.push(Vec::new());
+            }
+            _ => {
+                let frame = sprites.last_mut()
+                    .and_then(|s| s.frames.last_mut())
+                    .ok_or_else(|| err("cell before any frame"))?;
+                if words.len() != 4 { return Err(err("cell needs x drift y glyph")); }
+
+                let num = |w: &str| w.parse::<i8>().map_err(|_| err("bad number"));
+                let (x, drift, y) = (num(words[0])?, num(words[1])?, num(words[2])?);
+                let glyph = words[3].parse::<u8>().ok().filter(|&g| g < 8)
+                    .ok_or_else(|| err("glyph must be 0-7"))?;
+                frame.push([x as u8, drift as u8, y as u8, glyph]);
+            }
+        }
+    }
+
+    if sprites.is_empty() { return Err("no sprites".into()); }
+    if sprites.len() > 255 { return Err("more than 255 sprites".into()); }
+    for (i, sprite) in sprites.iter().enumerate() {
+        if sprite.frames.is_empty() || sprite.frames.len() > 255 {
+            return Err(format!("sprite {} needs 1-255 frames", i + 1));
+        }
+        if sprite.frames.iter().any(|f| f.len() > 255) {
+            return Err(format!("sprite {} has a frame with more than 255 cells", i + 1));
+        }
+    }
+
+    Ok(sprites)
+}
+
+fn encode(sprites: &[Sprite]) -> Vec<u8> {
+    let mut out = Vec::new();
+    out.extend_from_slice(MAGIC);
+    out.push(VERSION);
+    out.push(sprites.len() as u8);
+
+    for sprite in sprites {
+        out.push(sprite.slot);
+        out.push(sprite.frames.len() as u8);
+        for frame in &sprite.frames {
+            out.push(frame.len() as u8);
+            for cell in frame {
+                out.extend_from_slice(cell);
+            }
+        }
+    }
+
+    out
+}
@@ -3,17 +3,22 @@
 // Architecture:
 //   scene/  - Auto-generated scene data from AI analysis
 //   world/  - Terrain queries (depth, flow, normals)
-//   sim/    - Simulation entities (drops, splashes, streams)
+//   sim/    - Simulation entities (drops, splashes, streams) as pluggable systems
 //   render  - Output encoding
+//   sprite  - Splash sprite sheets
+//   math    - Portable trig (bit-identical native and wasm)
 
 use wasm_bindgen::prelude::*;
 
 mod scene;
-mod world;
-mod sim;
-mod render;
+pub mod world;
+pub mod sim;
+pub mod render;
+mod sprite;
+mod math;
 
 use sim::RainWorld as RainWorldInner;
+use sim::{ConfigPreset, PoolSizes, Precipitation, SimConfig, SplashMode, ViewportMode, WeatherState};
 
 // WASM wrapper - keeps the public API stable
 #[wasm_bindgen]
@@ -26,14 +31,59 @@ impl RainWorld {
         Self(RainWorldInner::new(w, h))
     }
 
+    /// Create with custom pool sizes (defaults: 3000 drops, 200 splashes, 500 streams)
+    pub fn with_capacity(w: u32, h: u32, drops: u32, splashes: u32, streams: u32) -> Self {
+        Self(RainWorldInner::with_capacity(w, h, pool_sizes(drops, splashes, streams)))
+    }
+
+    /// Change the screen size; live entities are moved to the new size
+    /// unless `set_reset_on_resize(true)`
     pub fn resize(&mut self, w: u32, h: u32) {
         self.0.resize(w, h);
     }
 
+    /// Clear entities on resize (rain restarts from the top) instead of rescaling
+    pub fn set_reset_on_resize(&mut self, reset: bool) {
+        self.0.set_reset_on_resize(reset);
+    }
+
+    pub fn reset_on_resize(&self) -> bool {
+        self.0.reset_on_resize()
+    }
+
+    /// How the scene fits the screen: 0 = stretch, 1 = cover, 2 = contain.
+    /// Anchors (0-1, 0.5 = centred) place it when cropped or letterboxed.
+    pub fn set_viewport(&mut self, mode: u8, anchor_x: f32, anchor_y: f32) {
+        if let Some(mode) = ViewportMode::from_u8(mode) {
+            self.0.set_viewport(mode, anchor_x, anchor_y);
+        }
+    }
+
+    /// Current viewport mode (see `set_viewport`)
+    pub fn viewport_mode(&self) -> u8 {
+        self.0.viewport().mode() as u8
+    }
+
+    /// Width / height of a screen cell in pixels (default 0.5)
+    pub fn set_cell_aspect(&mut self, aspect: f32) {
+        self.0.set_cell_aspect(aspect);
+    }
+
+    /// Where to draw the background: [left, top, width, height] in screen cells
+    pub fn viewport_rect(&self) -> Vec<f32> {
+        self.0.viewport().rect().to_vec()
+    }
+
+    /// Advance one fixed 1/60 s step
     pub fn tick(&mut self) {
         self.0.tick();
     }
 
+    /// Advance by elapsed real time in seconds (fixed steps + interpolation)
+    pub fn tick_dt(&mut self, seconds: f32) {
+        self.0.tick_dt(seconds);
+    }
+
     pub fn output_ptr(&self) -> *const u8 {
         self.0.output_ptr()
     }
@@ -49,4 +99,260 @@ impl RainWorld {
     pub fn height(&self) -> u32 {
         self.0.height()
     }
+
+    /// Base wind strength in cells per second (+ = right, 0 = calm)
+    pub fn set_wind(&mut self, strength: f32) {
+        self.0.set_wind(strength);
+    }
+
+    /// Extra drift for far drops (0 = uniform)
+    pub fn set_wind_depth_drift(&mut self, drift: f32) {
+        self.0.set_wind_depth_drift(drift);
+    }
+
+    /// Current wind including gusts (cells per second)
+    pub fn wind(&self) -> f32 {
+        self.0.wind()
+    }
+
+    /// Blend to a weather state over `seconds`
+    /// 0 = clear, 1 = drizzle, 2 = rain, 3 = downpour, 4 = storm
+    pub fn set_weather(&mut self, state: u8, seconds: f32) {
+        if let Some(state) = WeatherState::from_u8(state) {
+            self.0.set_weather(state, seconds);
+        }
+    }
+
+    /// Wander between weather states automatically
+    pub fn set_weather_auto(&mut self, auto: bool) {
+        self.0.set_weather_auto(auto);
+    }
+
+    /// Target weather state (see `set_weather`)
+    pub fn weather(&self) -> u8 {
+        self.0.weather() as u8
+    }
+
+    /// Blended intensity, 0 = clear to 1 = storm
+    pub fn weather_intensity(&self) -> f32 {
+        self.0.weather_intensity()
+    }
+
+    /// What falls: 0 = rain, 1 = snow, 2 = hail
+    pub fn set_precipitation(&mut self, kind: u8) {
+        if let Some(kind) = Precipitation::from_u8(kind) {
+            self.0.set_precipitation(kind);
+        }
+    }
+
+    /// Splash rendering: 0 = keyframed patterns, 1 = ballistic droplets
+    pub fn set_splash_mode(&mut self, mode: u8) {
+        if let Some(mode) = SplashMode::from_u8(mode) {
+            self.0.set_splash_mode(mode);
+        }
+    }
+
+    /// Current splash mode (see `set_splash_mode`)
+    pub fn splash_mode(&self) -> u8 {
+        self.0.splash_mode() as u8
+    }
+
+    /// Replace splash sprites with a sheet built by `splashsheet`
+    /// Returns false (and keeps the current sheet) if the data is invalid
+    pub fn load_splash_sheet(&mut self, bytes: &[u8]) -> bool {
+        self.0.load_splash_sheet(bytes).is_ok()
+    }
+
+    /// Back to the built-in splash sprites
+    pub fn reset_splash_sheet(&mut self) {
+        self.0.reset_splash_sheet();
+    }
+
+    /// Lightning flash brightness, 0-1 (decays after each strike)
+    pub fn lightning_flash(&self) -> f32 {
+        self.0.lightning_flash()
+    }
+
+    /// Current precipitation (see `set_precipitation`)
+    pub fn precipitation(&self) -> u8 {
+        self.0.precipitation() as u8
+    }
+
+    /// Reseed all random streams; call before the first tick for a
+    /// reproducible run (identical to a native build with the same seed)
+    pub fn set_seed(&mut self, seed: u32) {
+        self.0.set_seed(seed);
+    }
+
+    /// Seed of the current run
+    pub fn seed(&self) -> u32 {
+        self.0.seed()
+    }
+
+    /// Whole simulation state as a compact versioned blob
+    pub fn snapshot(&self) -> Vec<u8> {
+        self.0.snapshot()
+    }
+
+    /// Continue from a blob made by `snapshot` (at its screen size - call
+    /// `resize` after for a different canvas)
+    /// Returns false (and keeps the current state) if the data is invalid
+    pub fn restore(&mut self, bytes: &[u8]) -> bool {
+        self.0.restore(bytes).is_ok()
+    }
+
+    /// Start recording calls and frames for a bug report; `checksums`
+    /// hashes every frame's output so a replay can spot where it diverged
+    pub fn start_recording(&mut self, checksums: bool) {
+        self.0.start_recording(checksums);
+    }
+
+    /// Stop recording and return the log (empty if not recording)
+    pub fn stop_recording(&mut self) -> Vec<u8> {
+        self.0.stop_recording().unwrap_or_default()
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.0.is_recording()
+    }
+
+    /// Live drops
+    pub fn droplet_count(&self) -> u32 {
+        self.0.entities().drops.n as u32
+    }
+
+    /// Remove every live entity (puddles and settled snow stay)
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    /// Stats for the last frame as a flat array (see `STATS_LAYOUT` in
+    /// dropletEngine.ts): live, spawned, died and refused per kind, removal
+    /// causes, impacts per segment class, then ms per profiled phase
+    pub fn stats(&self) -> Vec<f32> {
+        self.0.stats().flatten()
+    }
+
+    /// Names of the timed phases, in the order `stats` lists them
+    pub fn stats_phase_names(&self) -> Vec<String> {
+        self.0.phase_names().map(String::from).collect()
+    }
+
+    /// Time each phase of a frame (off by default)
+    pub fn set_profiling(&mut self, enabled: bool) {
+        self.0.set_profiling(enabled);
+    }
+
+    /// Queue up to `cap` impact events per frame (0 = off, the default),
+    /// keeping a random `sample_rate` (0-1) of them
+    pub fn set_impact_events(&mut self, cap: u32, sample_rate: f32) {
+        self.0.set_impact_events(cap as usize, sample_rate);
+    }
+
+    /// Impacts of the last tick / tick_dt, `impact_count` records of 24
+    /// bytes (see `readImpacts` in dropletEngine.ts); valid until the next
+    /// tick
+    pub fn impact_ptr(&self) -> *const u8 {
+        self.0.impacts().as_ptr() as *const u8
+    }
+
+    pub fn impact_count(&self) -> u32 {
+        self.0.impacts().len() as u32
+    }
+
+    /// Impacts past the cap in the last tick / tick_dt
+    pub fn impact_overflow(&self) -> u32 {
+        self.0.impact_overflow()
+    }
+
+    /// Re-run a recording on this world (replacing its state). Returns the
+    /// first frame whose output differs from the recording, -1 if none
+    /// did, or -2 if the recording is invalid.
+    pub fn replay(&mut self, bytes: &[u8]) -> i32 {
+        match sim::replay(&mut self.0, bytes) {
+            Ok(report) => report.first_divergence.map_or(-1, |f| f as i32),
+            Err(_) => -2,
+        }
+    }
+
+    /// Resize the drop, splash and stream pools (live entities are kept
+    /// up to the new size)
+    pub fn set_capacity(&mut self, drops: u32, splashes: u32, streams: u32) {
+        self.0.set_capacity(pool_sizes(drops, splashes, streams));
+    }
+
+    /// Pool sizes: [drops, splashes, streams]
+    pub fn capacity(&self) -> Vec<u32> {
+        let c = self.0.capacity();
+        vec![c.drops as u32, c.splashes as u32, c.streams as u32]
+    }
+
+    /// Spawns lost to full pools: [drops, splashes, streams]
+    pub fn dropped_spawns(&self) -> Vec<u32> {
+        self.0.dropped_spawns().to_vec()
+    }
+
+    pub fn reset_dropped_spawns(&mut self) {
+        self.0.reset_dropped_spawns();
+    }
+
+    /// Set a tunable parameter by name (see `config_param_names`)
+    /// Returns false for unknown names and out-of-range values
+    pub fn set_config_param(&mut self, name: &str, value: f32) -> bool {
+        self.0.set_config_param(name, value).is_ok()
+    }
+
+    /// Current value of a tunable parameter (NaN if unknown)
+    pub fn config_param(&self, name: &str) -> f32 {
+        self.0.config().get(name).unwrap_or(f32::NAN)
+    }
+
+    /// Names of all tunable parameters
+    pub fn config_param_names(&self) -> Vec<String> {
+        SimConfig::names().map(String::from).collect()
+    }
+
+    /// Valid [min, max] of a tunable parameter (empty if unknown)
+    pub fn config_param_range(&self, name: &str) -> Vec<f32> {
+        SimConfig::range(name).map(|(lo, hi)| vec![lo, hi]).unwrap_or_default()
+    }
+
+    /// All tunable parameters as a flat JSON object
+    pub fn config_json(&self) -> String {
+        self.0.config().to_json()
+    }
+
+    /// Replace the parameters from JSON (missing keys use defaults)
+    /// Returns false (and keeps the current config) if the JSON is invalid
+    pub fn load_config_json(&mut self, json: &str) -> bool {
+        self.0.load_config_json(json).is_ok()
+    }
+
+    /// Reset parameters to a preset:
+    /// 0 = default, 1 = slow motion, 2 = soaked, 3 = light, 4 = flat
+    pub fn apply_config_preset(&mut self, preset: u8) {
+        if let Some(preset) = ConfigPreset::from_u8(preset) {
+            self.0.apply_config_preset(preset);
+        }
+    }
+}
+
+// Rust-side extension point (not exported to JS): custom systems are
+// registered on the inner world, e.g. from a crate that wraps this one
+impl RainWorld {
+    pub fn world(&self) -> &RainWorldInner {
+        &self.0
+    }
+
+    pub fn world_mut(&mut self) -> &mut RainWorldInner {
+        &mut self.0
+    }
+}
+
+fn pool_sizes(drops: u32, splashes: u32, streams: u32) -> PoolSizes {
+    PoolSizes {
+        drops: drops as usize,
+        splashes: splashes as usize,
+        streams: streams as usize,
+    }
 }
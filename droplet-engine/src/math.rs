@@ -0,0 +1,35 @@
+// math.rs - Portable trig
+//
+// std's sin/cos call into the platform libm, which differs between native
+// targets and wasm32 in the last bits. These use only exactly rounded
+// operations (+, *, floor), so results match everywhere.
+
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
+
+/// sin(x), within ~1e-5 of std for |x| < 100 (range reduction loses
+/// precision beyond that)
+pub fn sin(x: f32) -> f32 {
+    // Reduce to [-PI, PI], then fold into [-PI/2, PI/2]
+    let x = x - (x * (1.0 / TAU) + 0.5).floor() * TAU;
+    let x = if x > FRAC_PI_2 {
+        PI - x
+    } else if x < -FRAC_PI_2 {
+        -PI - x
+    } else {
+        x
+    };
+
+    // Taylor series to x^11
+    let x2 = x * x;
+    x * (1.0
+        + x2 * (-1.0 / 6.0
+        + x2 * (1.0 / 120.0
+        + x2 * (-1.0 / 5040.0
+        + x2 * (1.0 / 362_880.0
+        + x2 * (-1.0 / 39_916_800.0))))))
+}
+
+/// cos(x), same accuracy as sin
+pub fn cos(x: f32) -> f32 {
+    sin(x + FRAC_PI_2)
+}
@@ -5,16 +5,34 @@
 //   1-32     = drops (8 depths x 4 trail lengths)
 //   33-96    = splashes (8 depths x 8 chars)
 //   97-128   = streams (8 depths x 4 sizes)
+//   129-160  = ripples (8 depths x 4 fade levels)
+//   161-192  = flakes (8 depths x 4 sizes)
+//   193-200  = settled snow (8 depth levels, only in otherwise empty cells)
+//   201-232  = hail (8 depths x 4 states: falling, bouncing, rolling, melting)
+//   233-240  = lightning (4 fade levels x main/branch)
+//   241-255  = free for custom systems (see plot_raw)
+//
+// Moving entities take `alpha` (0-1, how far into the next fixed step the
+// frame is) and are drawn back along their velocity by `1 - alpha`, i.e.
+// interpolated between the last two steps. alpha = 1 draws them as-is.
 
-use crate::sim::{Droplets, Splashes, Streams};
+use crate::sim::{Droplets, Splashes, Spray, Streams, Ripples, Flakes, Snow, Hail, Lightning, Viewport, SPLASH_PUFF};
+use crate::sprite::SpriteSheet;
+use crate::math;
 
 const SPLASH_OFFSET: u8 = 33;
 const STREAM_OFFSET: u8 = 97;
+const RIPPLE_OFFSET: u8 = 129;
+const FLAKE_OFFSET: u8 = 161;
+const SNOW_OFFSET: u8 = 193;
+const HAIL_OFFSET: u8 = 201;
+const LIGHTNING_OFFSET: u8 = 233;
 
 pub struct Encoder {
     out: Vec<u8>,
     w: u32,
     h: u32,
+    sheet: SpriteSheet,
 }
 
 impl Encoder {
@@ -23,6 +41,7 @@ impl Encoder {
             out: vec![0; (w * h) as usize],
             w,
             h,
+            sheet: SpriteSheet::builtin(),
         }
     }
 
@@ -36,6 +55,11 @@ impl Encoder {
         self.out.fill(0);
     }
 
+    /// Replace the splash sprites
+    pub fn set_sheet(&mut self, sheet: SpriteSheet) {
+        self.sheet = sheet;
+    }
+
     pub fn ptr(&self) -> *const u8 {
         self.out.as_ptr()
     }
@@ -44,21 +68,43 @@ impl Encoder {
         self.out.len()
     }
 
+    pub fn as_slice(&self) -> &[u8] {
+        &self.out
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.out.is_empty()
+    }
+
+    /// Write an encoded value if it outranks the cell's current one (custom
+    /// systems draw with 241-255, or borrow a built-in encoding)
+    pub fn plot_raw(&mut self, x: i32, y: i32, value: u8) {
+        let (w, h) = (self.w as i32, self.h as i32);
+        if x >= 0 && x < w && y >= 0 && y < h {
+            let idx = (y * w + x) as usize;
+            if value > self.out[idx] { self.out[idx] = value; }
+        }
+    }
+
     /// Encode drops to output buffer
-    pub fn encode_drops(&mut self, drops: &Droplets, w: i32, h: i32) {
+    pub fn encode_drops(&mut self, drops: &Droplets, w: i32, h: i32, alpha: f32) {
+        let back = 1.0 - alpha;
         for i in 0..drops.n {
-            let x = drops.x[i] as i32;
-            let y = drops.y[i] as i32;
-            if x < 0 || x >= w { continue; }
+            let x = drops.x[i] - drops.vx[i] * back;
+            let y = (drops.y[i] - drops.v[i] * back) as i32;
 
             let z = drops.z[i];
             let bucket = (((1.0 - z) * 8.0) as u8).min(7);
             let trail = (5.0 - z * 4.0).max(1.0) as i32;
 
+            // Trail leans back along the velocity (wind)
+            let slope = drops.vx[i] / drops.v[i];
+
             for dy in 0..trail {
+                let px = (x - slope * dy as f32) as i32;
                 let py = y - dy;
-                if py >= 0 && py < h {
-                    let idx = (py * w + x) as usize;
+                if px >= 0 && px < w && py >= 0 && py < h {
+                    let idx = (py * w + px) as usize;
                     let enc = bucket * 4 + (dy.min(3) as u8) + 1;
                     if enc > self.out[idx] { self.out[idx] = enc; }
                 }
@@ -67,7 +113,7 @@ impl Encoder {
     }
 
     /// Encode splashes to output buffer
-    pub fn encode_splashes(&mut self, splashes: &Splashes, w: i32, h: i32, rng: &mut u32) {
+    pub fn encode_splashes(&mut self, splashes: &Splashes, w: i32, h: i32) {
         for i in 0..splashes.n {
             let x = splashes.x[i] as i32;
             let y = splashes.y[i] as i32;
@@ -75,19 +121,52 @@ impl Encoder {
 
             let bucket = (((1.0 - z) * 8.0) as u8).min(7);
             let scale = ((1.0 - z) * 2.5) as i32;
-            let frame = splashes.frame[i] / 3;
+            let frame = splashes.frame[i];
             let dir = splashes.dir[i] as i32;
             let typ = splashes.typ[i];
 
-            self.render_splash(x, y, bucket, scale, frame, dir, typ, w, h, rng);
+            // Splashes never move, so their position picks a stable variant
+            let variant = splashes.x[i].to_bits() >> 4;
+
+            // Tiny splashes (and muted materials)
+            if scale == 0 || typ == SPLASH_PUFF {
+                let f = frame as u32 * 8 / splashes.frames.max(1) as u32;
+                let c = if f < 3 { 0 } else if f < 6 { 2 } else { 6 };
+                self.put(x, y, c, bucket, w, h);
+                continue;
+            }
+
+            // Pattern from the sprite sheet by type and frame
+            let Self { out, sheet, .. } = self;
+            let Some(cells) = sheet.frame(typ.min(3), variant, frame, splashes.frames) else { continue };
+            for cell in cells {
+                let cx = x + cell.x as i32 * scale + cell.drift as i32 * dir;
+                let cy = y - cell.y as i32 * scale;
+                Self::plot(out, cx, cy, cell.glyph, bucket, w, h);
+            }
+        }
+    }
+
+    /// Encode ballistic spray droplets (splash characters)
+    pub fn encode_spray(&mut self, spray: &Spray, w: i32, h: i32, alpha: f32) {
+        let back = 1.0 - alpha;
+        for i in 0..spray.n {
+            let x = (spray.x[i] - spray.vx[i] * back) as i32;
+            let y = (spray.y[i] - spray.vy[i] * back) as i32;
+            let bucket = (((1.0 - spray.z[i]) * 8.0) as u8).min(7);
+
+            // Rising droplets are ', falling ones .
+            let c = if spray.vy[i] < 0.0 { 2 } else { 6 };
+            self.put(x, y, c, bucket, w, h);
         }
     }
 
     /// Encode streams to output buffer
-    pub fn encode_streams(&mut self, streams: &Streams, w: i32, h: i32) {
+    pub fn encode_streams(&mut self, streams: &Streams, w: i32, h: i32, alpha: f32) {
+        let back = 1.0 - alpha;
         for i in 0..streams.n {
-            let x = streams.x[i] as i32;
-            let y = streams.y[i] as i32;
+            let x = (streams.x[i] - streams.vx[i] * back) as i32;
+            let y = (streams.y[i] - streams.vy[i] * back) as i32;
             let z = streams.z[i];
 
             if x < 0 || x >= w || y < 0 || y >= h { continue; }
@@ -104,184 +183,124 @@ impl Encoder {
         }
     }
 
-    #[inline]
-    fn put(&mut self, x: i32, y: i32, char_idx: u8, bucket: u8, w: i32, h: i32) {
-        if (x as u32) < (w as u32) && (y as u32) < (h as u32) {
+    /// Encode ripples to output buffer
+    pub fn encode_ripples(&mut self, ripples: &Ripples, w: i32, h: i32) {
+        for i in 0..ripples.n {
+            let cx = ripples.x[i];
+            let cy = ripples.y[i];
+            let z = ripples.z[i];
+            let r = ripples.r[i];
+
+            let bucket = (((1.0 - z) * 8.0) as u8).min(7);
+            let enc = RIPPLE_OFFSET + bucket * 4 + Ripples::fade(ripples.age[i]);
+
+            // Water is seen at a glancing angle - far rings are flatter
+            let squash = 0.45 - z * 0.3;
+
+            self.ring((cx, cy), r, squash, enc, w, h);
+            if r > 2.0 {
+                // Inner echo ring
+                self.ring((cx, cy), r * 0.5, squash, enc, w, h);
+            }
+        }
+    }
+
+    /// Encode falling flakes to output buffer
+    pub fn encode_flakes(&mut self, flakes: &Flakes, w: i32, h: i32, alpha: f32) {
+        let back = 1.0 - alpha;
+        for i in 0..flakes.n {
+            let x = (flakes.x[i] - flakes.vx[i] * back) as i32;
+            let y = (flakes.y[i] - flakes.v[i] * back) as i32;
+            let z = flakes.z[i];
+
+            if x < 0 || x >= w || y < 0 || y >= h { continue; }
+
+            // Near flakes look bigger
+            let bucket = (((1.0 - z) * 8.0) as u8).min(7);
+            let size = (((1.0 - z) * 4.0) as u8).min(3);
+
             let idx = (y * w + x) as usize;
-            let enc = SPLASH_OFFSET + bucket * 8 + char_idx;
+            let enc = FLAKE_OFFSET + bucket * 4 + size;
             if enc > self.out[idx] { self.out[idx] = enc; }
         }
     }
 
-    fn render_splash(&mut self, cx: i32, gy: i32, b: u8, s: i32, f: u8, d: i32, typ: u8, w: i32, h: i32, _rng: &mut u32) {
-        // Tiny splashes
-        if s == 0 {
-            let c = if f < 3 { 0 } else if f < 6 { 2 } else { 6 };
-            self.put(cx, gy, c, b, w, h);
-            return;
-        }
+    /// Encode hailstones to output buffer
+    pub fn encode_hail(&mut self, hail: &Hail, w: i32, h: i32, alpha: f32) {
+        let back = 1.0 - alpha;
+        for i in 0..hail.n {
+            let x = (hail.x[i] - hail.vx[i] * back) as i32;
+            let y = (hail.y[i] - hail.vy[i] * back) as i32;
+            let z = hail.z[i];
+
+            if x < 0 || x >= w || y < 0 || y >= h { continue; }
+
+            let bucket = (((1.0 - z) * 8.0) as u8).min(7);
 
-        // Pattern by type and frame
-        match typ {
-            0 => self.splash_crown(cx, gy, b, s, f, d, w, h),
-            1 => self.splash_left(cx, gy, b, s, f, d, w, h),
-            2 => self.splash_right(cx, gy, b, s, f, d, w, h),
-            _ => self.splash_spray(cx, gy, b, s, f, d, w, h),
+            let idx = (y * w + x) as usize;
+            let enc = HAIL_OFFSET + bucket * 4 + hail.state[i];
+            if enc > self.out[idx] { self.out[idx] = enc; }
         }
     }
 
-    fn splash_crown(&mut self, cx: i32, gy: i32, b: u8, s: i32, f: u8, d: i32, w: i32, h: i32) {
-        match f {
-            0 => self.put(cx, gy, 0, b, w, h),
-            1 => {
-                self.put(cx - s + d, gy, 4, b, w, h);
-                self.put(cx, gy, 0, b, w, h);
-                self.put(cx + s + d, gy, 5, b, w, h);
-            }
-            2 => {
-                self.put(cx + d, gy - s, 1, b, w, h);
-                self.put(cx - s + d, gy, 4, b, w, h);
-                self.put(cx + s + d, gy, 5, b, w, h);
-            }
-            3 => {
-                self.put(cx - s*2 + d, gy - s, 4, b, w, h);
-                self.put(cx + d, gy - s, 1, b, w, h);
-                self.put(cx + s*2 + d, gy - s, 5, b, w, h);
-                self.put(cx - s, gy, 4, b, w, h);
-                self.put(cx + s, gy, 5, b, w, h);
-            }
-            4 => {
-                self.put(cx - s*2 + d*2, gy - s*2, 2, b, w, h);
-                self.put(cx + d, gy - s*2, 2, b, w, h);
-                self.put(cx + s*2 + d*2, gy - s*2, 2, b, w, h);
-                self.put(cx - s*2 + d, gy - s, 4, b, w, h);
-                self.put(cx + s*2 + d, gy - s, 5, b, w, h);
-            }
-            5 => {
-                self.put(cx - s*3 + d*2, gy - s*2, 2, b, w, h);
-                self.put(cx + d, gy - s*2, 2, b, w, h);
-                self.put(cx + s*3 + d*2, gy - s*2, 2, b, w, h);
-            }
-            6 => {
-                self.put(cx - s*2 + d, gy - s, 2, b, w, h);
-                self.put(cx + s*2 + d, gy - s, 2, b, w, h);
-            }
-            _ => {
-                self.put(cx - s + d, gy, 6, b, w, h);
-                self.put(cx + s + d, gy, 6, b, w, h);
-            }
+    /// Encode the current lightning bolt (drawn over everything)
+    pub fn encode_lightning(&mut self, lightning: &Lightning, w: i32, h: i32) {
+        if !lightning.visible() { return; }
+
+        let fade = lightning.fade();
+        for i in 0..lightning.n {
+            let x = lightning.x[i] as i32;
+            let y = lightning.y[i] as i32;
+
+            if x < 0 || x >= w || y < 0 || y >= h { continue; }
+
+            let idx = (y * w + x) as usize;
+            self.out[idx] = LIGHTNING_OFFSET + fade * 2 + lightning.branch[i] as u8;
         }
     }
 
-    fn splash_left(&mut self, cx: i32, gy: i32, b: u8, s: i32, f: u8, d: i32, w: i32, h: i32) {
-        match f {
-            0 => self.put(cx, gy, 0, b, w, h),
-            1 => {
-                self.put(cx - s + d, gy, 4, b, w, h);
-                self.put(cx, gy, 0, b, w, h);
-            }
-            2 => {
-                self.put(cx - s + d, gy - s, 4, b, w, h);
-                self.put(cx + d, gy - s, 1, b, w, h);
-                self.put(cx - s*2 + d, gy, 4, b, w, h);
-            }
-            3 => {
-                self.put(cx - s*2 + d, gy - s*2, 2, b, w, h);
-                self.put(cx - s + d, gy - s, 4, b, w, h);
-                self.put(cx + d, gy - s, 1, b, w, h);
-                self.put(cx - s*3 + d, gy, 4, b, w, h);
-            }
-            4 => {
-                self.put(cx - s*3 + d, gy - s*2, 2, b, w, h);
-                self.put(cx - s + d, gy - s*2, 2, b, w, h);
-                self.put(cx - s*2 + d, gy - s, 4, b, w, h);
-                self.put(cx + d, gy - s, 1, b, w, h);
-            }
-            5 => {
-                self.put(cx - s*4 + d, gy - s*2, 2, b, w, h);
-                self.put(cx - s*2 + d, gy - s*2, 2, b, w, h);
-                self.put(cx - s*3 + d, gy - s, 4, b, w, h);
-            }
-            6 => {
-                self.put(cx - s*3 + d, gy - s, 2, b, w, h);
-                self.put(cx - s + d, gy - s, 2, b, w, h);
+    /// Encode settled snow into cells nothing else was drawn in
+    pub fn encode_snow(&mut self, snow: &Snow, w: i32, h: i32, vp: &Viewport) {
+        for y in 0..h {
+            let by = vp.row(y as f32);
+            for x in 0..w {
+                let idx = (y * w + x) as usize;
+                if self.out[idx] != 0 { continue; }
+
+                let depth = snow.depth(vp.col(x as f32), by);
+                if depth == 0 { continue; }
+
+                self.out[idx] = SNOW_OFFSET + depth / 32;
             }
-            _ => self.put(cx - s*2 + d, gy, 6, b, w, h),
         }
     }
 
-    fn splash_right(&mut self, cx: i32, gy: i32, b: u8, s: i32, f: u8, d: i32, w: i32, h: i32) {
-        match f {
-            0 => self.put(cx, gy, 0, b, w, h),
-            1 => {
-                self.put(cx, gy, 0, b, w, h);
-                self.put(cx + s + d, gy, 5, b, w, h);
+    /// Plot an ellipse outline around (cx, cy) (rx = r, ry = r * squash)
+    fn ring(&mut self, (cx, cy): (f32, f32), r: f32, squash: f32, enc: u8, w: i32, h: i32) {
+        let steps = ((r * 6.0) as i32).clamp(4, 48);
+        for k in 0..steps {
+            let a = k as f32 * (std::f32::consts::TAU / steps as f32);
+            let x = (cx + math::cos(a) * r).round() as i32;
+            let y = (cy + math::sin(a) * r * squash).round() as i32;
+            if (x as u32) < (w as u32) && (y as u32) < (h as u32) {
+                let idx = (y * w + x) as usize;
+                if enc > self.out[idx] { self.out[idx] = enc; }
             }
-            2 => {
-                self.put(cx + d, gy - s, 1, b, w, h);
-                self.put(cx + s + d, gy - s, 5, b, w, h);
-                self.put(cx + s*2 + d, gy, 5, b, w, h);
-            }
-            3 => {
-                self.put(cx + d, gy - s, 1, b, w, h);
-                self.put(cx + s + d, gy - s, 5, b, w, h);
-                self.put(cx + s*2 + d, gy - s*2, 2, b, w, h);
-                self.put(cx + s*3 + d, gy, 5, b, w, h);
-            }
-            4 => {
-                self.put(cx + d, gy - s, 1, b, w, h);
-                self.put(cx + s*2 + d, gy - s, 5, b, w, h);
-                self.put(cx + s + d, gy - s*2, 2, b, w, h);
-                self.put(cx + s*3 + d, gy - s*2, 2, b, w, h);
-            }
-            5 => {
-                self.put(cx + s*3 + d, gy - s, 5, b, w, h);
-                self.put(cx + s*2 + d, gy - s*2, 2, b, w, h);
-                self.put(cx + s*4 + d, gy - s*2, 2, b, w, h);
-            }
-            6 => {
-                self.put(cx + s + d, gy - s, 2, b, w, h);
-                self.put(cx + s*3 + d, gy - s, 2, b, w, h);
-            }
-            _ => self.put(cx + s*2 + d, gy, 6, b, w, h),
         }
     }
 
-    fn splash_spray(&mut self, cx: i32, gy: i32, b: u8, s: i32, f: u8, d: i32, w: i32, h: i32) {
-        match f {
-            0 => self.put(cx, gy, 0, b, w, h),
-            1 => {
-                self.put(cx + d, gy, 0, b, w, h);
-                self.put(cx - s + d, gy, 2, b, w, h);
-                self.put(cx + s + d, gy, 2, b, w, h);
-            }
-            2 => {
-                self.put(cx + d*2, gy - s, 2, b, w, h);
-                self.put(cx - s + d, gy - s, 2, b, w, h);
-                self.put(cx + s*2 + d, gy, 2, b, w, h);
-            }
-            3 => {
-                self.put(cx + d*2, gy - s*2, 2, b, w, h);
-                self.put(cx - s*2 + d, gy - s, 2, b, w, h);
-                self.put(cx + s + d, gy - s, 2, b, w, h);
-                self.put(cx + s*3 + d, gy, 2, b, w, h);
-            }
-            4 => {
-                self.put(cx - s + d, gy - s*2, 2, b, w, h);
-                self.put(cx + s*2 + d, gy - s*2, 2, b, w, h);
-                self.put(cx - s*2 + d, gy - s, 2, b, w, h);
-                self.put(cx + s*3 + d, gy - s, 2, b, w, h);
-            }
-            5 => {
-                self.put(cx - s*2 + d, gy - s*2, 2, b, w, h);
-                self.put(cx + s + d, gy - s*2, 2, b, w, h);
-                self.put(cx + s*3 + d, gy - s, 2, b, w, h);
-            }
-            6 => {
-                self.put(cx - s + d, gy - s, 2, b, w, h);
-                self.put(cx + s*2 + d, gy - s, 2, b, w, h);
-            }
-            _ => self.put(cx + d, gy, 6, b, w, h),
+    #[inline]
+    fn put(&mut self, x: i32, y: i32, char_idx: u8, bucket: u8, w: i32, h: i32) {
+        Self::plot(&mut self.out, x, y, char_idx, bucket, w, h);
+    }
+
+    #[inline]
+    fn plot(out: &mut [u8], x: i32, y: i32, char_idx: u8, bucket: u8, w: i32, h: i32) {
+        if (x as u32) < (w as u32) && (y as u32) < (h as u32) {
+            let idx = (y * w + x) as usize;
+            let enc = SPLASH_OFFSET + bucket * 8 + char_idx;
+            if enc > out[idx] { out[idx] = enc; }
         }
     }
+
 }
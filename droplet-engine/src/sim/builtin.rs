@@ -0,0 +1,263 @@
+// builtin.rs - The built-in entities as systems
+//
+// Thin wrappers: each forwards to its pool's update and the matching
+// Encoder pass. register() adds them at the ORDER_* keys, which reproduce
+// the hardwired order tick() used to have.
+
+use super::{rng, Droplets, Precipitation, Splashes, Streams, System, Systems, StepContext, RenderContext, Entities, Viewport};
+use super::{ORDER_LIGHTNING, ORDER_SPAWN, ORDER_DROPS, ORDER_SPLASHES, ORDER_STREAMS, ORDER_RIPPLES};
+use super::{ORDER_FLAKES, ORDER_HAIL, ORDER_PUDDLES, ORDER_SNOW};
+use crate::render::Encoder;
+
+/// Add every built-in system
+pub fn register(systems: &mut Systems, seed: u32) {
+    // Names are unique, so registering into an empty list can't fail
+    let builtins: [(i32, u32, Box<dyn System>); 10] = [
+        (ORDER_LIGHTNING, rng::named("lightning"), Box::new(LightningSystem)),
+        (ORDER_SPAWN, rng::SPAWN, Box::new(SpawnSystem)),
+        (ORDER_DROPS, rng::DROPLET, Box::new(DropSystem)),
+        (ORDER_SPLASHES, rng::named("splashes"), Box::new(SplashSystem)),
+        (ORDER_STREAMS, rng::named("streams"), Box::new(StreamSystem)),
+        (ORDER_RIPPLES, rng::named("ripples"), Box::new(RippleSystem)),
+        (ORDER_FLAKES, rng::named("flakes"), Box::new(FlakeSystem)),
+        (ORDER_HAIL, rng::named("hail"), Box::new(HailSystem)),
+        (ORDER_PUDDLES, rng::named("puddles"), Box::new(PuddleSystem)),
+        (ORDER_SNOW, rng::named("snow"), Box::new(SnowSystem)),
+    ];
+    for (order, stream, system) in builtins {
+        let _ = systems.add_with_stream(order, stream, system, seed);
+    }
+}
+
+// Move screen positions to the same scene position under a new mapping
+fn stretch(x: &mut [f32], y: &mut [f32], from: &Viewport, to: &Viewport) {
+    x.iter_mut().for_each(|v| *v = to.screen_x(from.bg_x(*v)));
+    y.iter_mut().for_each(|v| *v = to.screen_y(from.bg_y(*v)));
+}
+
+/// Bolts and flashes
+struct LightningSystem;
+
+impl System for LightningSystem {
+    fn name(&self) -> &'static str { "lightning" }
+
+    fn update(&mut self, ctx: &mut StepContext) {
+        ctx.entities.lightning.update(ctx.weather.intensity(), ctx.w, ctx.h, &ctx.viewport, ctx.rng);
+    }
+
+    fn render(&self, ctx: &RenderContext, encoder: &mut Encoder) {
+        encoder.encode_lightning(&ctx.entities.lightning, ctx.w as i32, ctx.h as i32);
+    }
+
+    fn rescale(&mut self, entities: &mut Entities, _from: &Viewport, _to: &Viewport) {
+        // A bolt lasts a few frames; scaling its cells would leave gaps
+        entities.lightning.clear();
+    }
+
+    fn reset(&mut self, entities: &mut Entities) {
+        entities.lightning.clear();
+    }
+}
+
+/// New drops (or flakes, or hail) at the top, and drips off overhangs
+struct SpawnSystem;
+
+impl System for SpawnSystem {
+    fn name(&self) -> &'static str { "spawn" }
+
+    fn update(&mut self, ctx: &mut StepContext) {
+        let (w, h) = (ctx.w as f32, ctx.h as f32);
+        match ctx.precipitation {
+            Precipitation::Rain => Droplets::spawn(ctx),
+            Precipitation::Snow => ctx.entities.flakes.spawn(ctx.spawn_count, w, h, ctx.wind, ctx.rng),
+            Precipitation::Hail => ctx.entities.hail.spawn(ctx.spawn_count, w, ctx.wind, ctx.rng),
+        }
+        let e = &mut *ctx.entities;
+        e.drips.update(&mut e.drops, &ctx.viewport, ctx.config, ctx.rng);
+    }
+}
+
+struct DropSystem;
+
+impl System for DropSystem {
+    fn name(&self) -> &'static str { "drops" }
+
+    fn update(&mut self, ctx: &mut StepContext) {
+        Droplets::update(ctx);
+    }
+
+    fn render(&self, ctx: &RenderContext, encoder: &mut Encoder) {
+        encoder.encode_drops(&ctx.entities.drops, ctx.w as i32, ctx.h as i32, ctx.alpha);
+    }
+
+    fn rescale(&mut self, entities: &mut Entities, from: &Viewport, to: &Viewport) {
+        let p = &mut entities.drops;
+        stretch(&mut p.x[..p.n], &mut p.y[..p.n], from, to);
+    }
+
+    fn reset(&mut self, entities: &mut Entities) {
+        entities.drops.clear();
+    }
+}
+
+/// Splash animations and their spray droplets
+struct SplashSystem;
+
+impl System for SplashSystem {
+    fn name(&self) -> &'static str { "splashes" }
+
+    fn update(&mut self, ctx: &mut StepContext) {
+        ctx.entities.splashes.update();
+        Splashes::update_spray(ctx);
+    }
+
+    fn render(&self, ctx: &RenderContext, encoder: &mut Encoder) {
+        let (w, h) = (ctx.w as i32, ctx.h as i32);
+        encoder.encode_splashes(&ctx.entities.splashes, w, h);
+        encoder.encode_spray(&ctx.entities.splashes.spray, w, h, ctx.alpha);
+    }
+
+    fn rescale(&mut self, entities: &mut Entities, from: &Viewport, to: &Viewport) {
+        let p = &mut entities.splashes;
+        stretch(&mut p.x[..p.n], &mut p.y[..p.n], from, to);
+        let p = &mut p.spray;
+        stretch(&mut p.x[..p.n], &mut p.y[..p.n], from, to);
+    }
+
+    fn reset(&mut self, entities: &mut Entities) {
+        entities.splashes.clear();
+        entities.splashes.spray.clear();
+    }
+}
+
+struct StreamSystem;
+
+impl System for StreamSystem {
+    fn name(&self) -> &'static str { "streams" }
+
+    fn update(&mut self, ctx: &mut StepContext) {
+        Streams::update(ctx);
+    }
+
+    fn render(&self, ctx: &RenderContext, encoder: &mut Encoder) {
+        encoder.encode_streams(&ctx.entities.streams, ctx.w as i32, ctx.h as i32, ctx.alpha);
+    }
+
+    fn rescale(&mut self, entities: &mut Entities, from: &Viewport, to: &Viewport) {
+        let p = &mut entities.streams;
+        stretch(&mut p.x[..p.n], &mut p.y[..p.n], from, to);
+    }
+
+    fn reset(&mut self, entities: &mut Entities) {
+        entities.streams.clear();
+    }
+}
+
+struct RippleSystem;
+
+impl System for RippleSystem {
+    fn name(&self) -> &'static str { "ripples" }
+
+    fn update(&mut self, ctx: &mut StepContext) {
+        ctx.entities.ripples.update();
+    }
+
+    fn render(&self, ctx: &RenderContext, encoder: &mut Encoder) {
+        encoder.encode_ripples(&ctx.entities.ripples, ctx.w as i32, ctx.h as i32);
+    }
+
+    fn rescale(&mut self, entities: &mut Entities, from: &Viewport, to: &Viewport) {
+        let p = &mut entities.ripples;
+        stretch(&mut p.x[..p.n], &mut p.y[..p.n], from, to);
+    }
+
+    fn reset(&mut self, entities: &mut Entities) {
+        entities.ripples.clear();
+    }
+}
+
+struct FlakeSystem;
+
+impl System for FlakeSystem {
+    fn name(&self) -> &'static str { "flakes" }
+
+    fn update(&mut self, ctx: &mut StepContext) {
+        let e = &mut *ctx.entities;
+        e.flakes.update(
+            ctx.w as f32,
+            ctx.h as f32,
+            &ctx.viewport,
+            &mut e.snow,
+            ctx.wind,
+            ctx.config,
+        );
+    }
+
+    fn render(&self, ctx: &RenderContext, encoder: &mut Encoder) {
+        encoder.encode_flakes(&ctx.entities.flakes, ctx.w as i32, ctx.h as i32, ctx.alpha);
+    }
+
+    fn rescale(&mut self, entities: &mut Entities, from: &Viewport, to: &Viewport) {
+        let p = &mut entities.flakes;
+        stretch(&mut p.x[..p.n], &mut p.y[..p.n], from, to);
+    }
+
+    fn reset(&mut self, entities: &mut Entities) {
+        entities.flakes.clear();
+    }
+}
+
+struct HailSystem;
+
+impl System for HailSystem {
+    fn name(&self) -> &'static str { "hail" }
+
+    fn update(&mut self, ctx: &mut StepContext) {
+        ctx.entities.hail.update(
+            ctx.w as f32,
+            ctx.h as f32,
+            &ctx.viewport,
+            ctx.pyramid,
+            ctx.config,
+        );
+    }
+
+    fn render(&self, ctx: &RenderContext, encoder: &mut Encoder) {
+        encoder.encode_hail(&ctx.entities.hail, ctx.w as i32, ctx.h as i32, ctx.alpha);
+    }
+
+    fn rescale(&mut self, entities: &mut Entities, from: &Viewport, to: &Viewport) {
+        let p = &mut entities.hail;
+        stretch(&mut p.x[..p.n], &mut p.y[..p.n], from, to);
+    }
+
+    fn reset(&mut self, entities: &mut Entities) {
+        entities.hail.clear();
+    }
+}
+
+/// Puddles drying out (not drawn; drops landing on them ripple)
+struct PuddleSystem;
+
+impl System for PuddleSystem {
+    fn name(&self) -> &'static str { "puddles" }
+
+    fn update(&mut self, ctx: &mut StepContext) {
+        ctx.entities.puddles.update();
+    }
+}
+
+/// Settled snow: builds while snowing, melts otherwise
+struct SnowSystem;
+
+impl System for SnowSystem {
+    fn name(&self) -> &'static str { "snow" }
+
+    fn update(&mut self, ctx: &mut StepContext) {
+        ctx.entities.snow.update(ctx.precipitation == Precipitation::Snow);
+    }
+
+    fn render(&self, ctx: &RenderContext, encoder: &mut Encoder) {
+        encoder.encode_snow(&ctx.entities.snow, ctx.w as i32, ctx.h as i32, &ctx.viewport);
+    }
+}
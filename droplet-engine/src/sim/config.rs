@@ -0,0 +1,252 @@
+// config.rs - Tunable simulation parameters
+//
+// Values that used to be module constants, so the look can be tuned live.
+// Every parameter has a name and a valid range; setters reject anything
+// outside it. Configs round-trip through a flat JSON object of numbers
+// ({"vel_near": 102, ...}) and a few named presets cover common looks.
+//
+// Units are the user-facing ones (cells per second, seconds); systems
+// convert with STEP / ticks() where they read them.
+
+use super::{ticks, Viewport};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct SimConfig {
+    pub vel_near: f32,       // fall speed of near drops (cells/s)
+    pub vel_far: f32,        // ... and far drops (perspective)
+    pub ground_near: f32,    // ground line for near entities (fraction of scene height)
+    pub ground_far: f32,     // ... and far ones (the horizon)
+    pub splash_chance: f32,  // ground line splash chance, scaled by material
+    pub slide_chance: f32,   // stream spawn chance, scales material and spray
+    pub depth_margin: u8,    // depth tolerance for surface hits
+    pub flow_speed: f32,     // stream speed along the flow field (cells/s)
+    pub flow_lifetime: f32,  // seconds a stream runs before pooling
+    pub splash_time: f32,    // seconds a splash animation lasts
+}
+
+/// Named starting points for tuning
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(u8)]
+pub enum ConfigPreset {
+    Default = 0,
+    SlowMotion = 1,  // everything at a third of the speed, long splashes
+    Soaked = 2,      // more splashes and longer, faster run-off
+    Light = 3,       // few splashes, short streams
+    Flat = 4,        // ground line at the bottom for every depth
+}
+
+impl ConfigPreset {
+    pub fn from_u8(v: u8) -> Option<Self> {
+        match v {
+            0 => Some(Self::Default),
+            1 => Some(Self::SlowMotion),
+            2 => Some(Self::Soaked),
+            3 => Some(Self::Light),
+            4 => Some(Self::Flat),
+            _ => None,
+        }
+    }
+}
+
+struct Param {
+    name: &'static str,
+    min: f32,
+    max: f32,
+    get: fn(&SimConfig) -> f32,
+    set: fn(&mut SimConfig, f32),
+}
+
+// Tick counts are stored in u8, so times stay under 255 ticks
+static PARAMS: [Param; 10] = [
+    Param { name: "vel_near", min: 10.0, max: 400.0, get: |c| c.vel_near, set: |c, v| c.vel_near = v },
+    Param { name: "vel_far", min: 5.0, max: 200.0, get: |c| c.vel_far, set: |c, v| c.vel_far = v },
+    Param { name: "ground_near", min: 0.1, max: 1.0, get: |c| c.ground_near, set: |c, v| c.ground_near = v },
+    Param { name: "ground_far", min: 0.1, max: 1.0, get: |c| c.ground_far, set: |c, v| c.ground_far = v },
+    Param { name: "splash_chance", min: 0.0, max: 1.0, get: |c| c.splash_chance, set: |c, v| c.splash_chance = v },
+    Param { name: "slide_chance", min: 0.0, max: 2.0, get: |c| c.slide_chance, set: |c, v| c.slide_chance = v },
+    Param { name: "depth_margin", min: 0.0, max: 255.0, get: |c| c.depth_margin as f32, set: |c, v| c.depth_margin = v.round() as u8 },
+    Param { name: "flow_speed", min: 1.0, max: 120.0, get: |c| c.flow_speed, set: |c, v| c.flow_speed = v },
+    Param { name: "flow_lifetime", min: 0.1, max: 4.0, get: |c| c.flow_lifetime, set: |c, v| c.flow_lifetime = v },
+    Param { name: "splash_time", min: 0.05, max: 4.0, get: |c| c.splash_time, set: |c, v| c.splash_time = v },
+];
+
+impl SimConfig {
+    pub const DEFAULT: Self = Self {
+        vel_near: 102.0,
+        vel_far: 21.0,
+        ground_near: 1.0,
+        ground_far: 0.4,
+        splash_chance: 0.7,
+        slide_chance: 1.0,
+        depth_margin: 48,
+        flow_speed: 24.0,
+        flow_lifetime: 2.0,
+        splash_time: 0.4,
+    };
+
+    pub fn preset(preset: ConfigPreset) -> Self {
+        let d = Self::DEFAULT;
+        match preset {
+            ConfigPreset::Default => d,
+            ConfigPreset::SlowMotion => Self {
+                vel_near: d.vel_near / 3.0,
+                vel_far: d.vel_far / 3.0,
+                flow_speed: d.flow_speed / 3.0,
+                flow_lifetime: 4.0,
+                splash_time: 1.2,
+                ..d
+            },
+            ConfigPreset::Soaked => Self {
+                splash_chance: 1.0,
+                slide_chance: 2.0,
+                flow_speed: 30.0,
+                flow_lifetime: 3.5,
+                ..d
+            },
+            ConfigPreset::Light => Self {
+                splash_chance: 0.35,
+                slide_chance: 0.35,
+                flow_lifetime: 1.0,
+                splash_time: 0.3,
+                ..d
+            },
+            ConfigPreset::Flat => Self { ground_far: 1.0, ..d },
+        }
+    }
+
+    /// Names of all parameters, in export order
+    pub fn names() -> impl Iterator<Item = &'static str> {
+        PARAMS.iter().map(|p| p.name)
+    }
+
+    /// Valid (min, max) for a parameter
+    pub fn range(name: &str) -> Option<(f32, f32)> {
+        Self::param(name).map(|p| (p.min, p.max))
+    }
+
+    pub fn get(&self, name: &str) -> Option<f32> {
+        Self::param(name).map(|p| (p.get)(self))
+    }
+
+    /// Set one parameter; rejects unknown names and out-of-range values
+    pub fn set(&mut self, name: &str, value: f32) -> Result<(), &'static str> {
+        let p = Self::param(name).ok_or("unknown config parameter")?;
+        if !(value >= p.min && value <= p.max) { return Err("config value out of range"); }
+        (p.set)(self, value);
+        Ok(())
+    }
+
+    /// Ticks a stream runs for
+    pub fn flow_ticks(&self) -> u8 {
+        ticks(self.flow_lifetime) as u8
+    }
+
+    /// Ticks a splash animation lasts
+    pub fn splash_frames(&self) -> u8 {
+        ticks(self.splash_time).max(1) as u8
+    }
+
+    /// Ground line at depth z (screen rows; fractions are of the scene's height)
+    #[inline(always)]
+    pub fn ground(&self, vp: &Viewport, z: f32) -> f32 {
+        vp.top + vp.height * (self.ground_near + (self.ground_far - self.ground_near) * z)
+    }
+
+    /// Flat JSON object with every parameter
+    pub fn to_json(self) -> String {
+        let fields: Vec<String> = PARAMS.iter()
+            .map(|p| format!("\"{}\": {}", p.name, (p.get)(&self)))
+            .collect();
+        format!("{{{}}}", fields.join(", "))
+    }
+
+    /// Parse a flat JSON object of numbers. Missing parameters keep their
+    /// defaults; unknown names and out-of-range values are errors.
+    pub fn from_json(text: &str) -> Result<Self, &'static str> {
+        let mut config = Self::DEFAULT;
+        let mut r = Reader { bytes: text.as_bytes(), pos: 0 };
+
+        r.expect(b'{')?;
+        if !r.eat(b'}') {
+            loop {
+                let name = r.string()?;
+                r.expect(b':')?;
+                let value = r.number()?;
+                config.set(name, value)?;
+
+                if r.eat(b',') { continue; }
+                r.expect(b'}')?;
+                break;
+            }
+        }
+
+        r.skip_ws();
+        if r.pos != r.bytes.len() { return Err("trailing data after config"); }
+        Ok(config)
+    }
+
+    fn param(name: &str) -> Option<&'static Param> {
+        PARAMS.iter().find(|p| p.name == name)
+    }
+}
+
+impl Default for SimConfig {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+// Just enough JSON for a flat object of numbers
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn skip_ws(&mut self) {
+        while self.pos < self.bytes.len() && self.bytes[self.pos].is_ascii_whitespace() {
+            self.pos += 1;
+        }
+    }
+
+    fn eat(&mut self, c: u8) -> bool {
+        self.skip_ws();
+        if self.bytes.get(self.pos) == Some(&c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: u8) -> Result<(), &'static str> {
+        if self.eat(c) { Ok(()) } else { Err("malformed config JSON") }
+    }
+
+    fn string(&mut self) -> Result<&'a str, &'static str> {
+        self.expect(b'"')?;
+        let start = self.pos;
+        while self.pos < self.bytes.len() && self.bytes[self.pos] != b'"' {
+            // Parameter names never need escapes
+            if self.bytes[self.pos] == b'\\' { return Err("malformed config JSON"); }
+            self.pos += 1;
+        }
+        if self.pos == self.bytes.len() { return Err("malformed config JSON"); }
+
+        let s = std::str::from_utf8(&self.bytes[start..self.pos]).map_err(|_| "malformed config JSON")?;
+        self.pos += 1;
+        Ok(s)
+    }
+
+    fn number(&mut self) -> Result<f32, &'static str> {
+        self.skip_ws();
+        let start = self.pos;
+        while self.pos < self.bytes.len() && matches!(self.bytes[self.pos], b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E') {
+            self.pos += 1;
+        }
+
+        // Only ASCII was consumed, so this can't fail
+        let s = std::str::from_utf8(&self.bytes[start..self.pos]).unwrap_or("");
+        s.parse().map_err(|_| "config values must be numbers")
+    }
+}
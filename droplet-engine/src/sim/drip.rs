@@ -0,0 +1,97 @@
+// drip.rs - Water dripping off overhang edges
+//
+// Each BG_DRIPS point drains a patch of surface just above it. Impacts on
+// that patch fill the drip point; it releases drops at a rate proportional
+// to the water it holds.
+
+use super::{Droplets, RainWorld, SimConfig, SnapReader, SnapWriter, Viewport};
+use crate::scene::{BG_WIDTH, BG_HEIGHT, BG_DEPTH, BG_DRIPS};
+
+const CATCH_ROWS: usize = 12;    // rows above a drip point that drain into it
+const CATCH_COLS: usize = 3;     // columns either side
+const CATCH_MARGIN: u8 = 24;     // depth tolerance for "same surface"
+const DRIP_CAPACITY: f32 = 8.0;  // max water held per point
+const DRIP_RATE: f32 = 0.02;     // release chance per tick per unit of water
+const NO_DRIP: u16 = u16::MAX;
+
+pub struct Drips {
+    water: Vec<f32>,  // per drip point
+    catch: Vec<u16>,  // BG_WIDTH x BG_HEIGHT -> drip index (NO_DRIP if none)
+}
+
+impl Drips {
+    pub fn new() -> Self {
+        let mut catch = vec![NO_DRIP; BG_WIDTH * BG_HEIGHT];
+
+        for (i, &(dx, dy)) in BG_DRIPS.iter().enumerate() {
+            let (dx, dy) = (dx as usize, dy as usize);
+            let edge_depth = BG_DEPTH[dy][dx];
+
+            let x0 = dx.saturating_sub(CATCH_COLS);
+            let x1 = (dx + CATCH_COLS).min(BG_WIDTH - 1);
+            for y in dy.saturating_sub(CATCH_ROWS)..=dy {
+                for x in x0..=x1 {
+                    // Only the surface the edge belongs to drains here
+                    if BG_DEPTH[y][x].abs_diff(edge_depth) < CATCH_MARGIN {
+                        catch[y * BG_WIDTH + x] = i as u16;
+                    }
+                }
+            }
+        }
+
+        Self {
+            water: vec![0.0; BG_DRIPS.len()],
+            catch,
+        }
+    }
+
+    /// Record an impact at a background pixel
+    #[inline]
+    pub fn collect(&mut self, bx: usize, by: usize) {
+        if bx >= BG_WIDTH || by >= BG_HEIGHT { return; }
+
+        let i = self.catch[by * BG_WIDTH + bx];
+        if i != NO_DRIP {
+            let w = &mut self.water[i as usize];
+            *w = (*w + 1.0).min(DRIP_CAPACITY);
+        }
+    }
+
+    /// Release drops from points holding water
+    pub fn update(&mut self, drops: &mut Droplets, vp: &Viewport, cfg: &SimConfig, rng: &mut u32) {
+        for (i, &(dx, dy)) in BG_DRIPS.iter().enumerate() {
+            let w = self.water[i];
+            if w < 1.0 || RainWorld::rand(rng) >= w * DRIP_RATE { continue; }
+
+            // Just below the edge, at the edge's depth
+            let x = vp.screen_x(dx as f32 + 0.5);
+            let y = vp.screen_y(dy as f32 + 1.0);
+            let z = 1.0 - (BG_DEPTH[dy as usize][dx as usize] as f32 + 0.5) / 255.0;
+
+            drops.spawn_at(x, y, z, cfg, rng);
+            self.water[i] = w - 1.0;
+        }
+    }
+
+    /// Water held per point (the catch map is rebuilt from the scene)
+    pub fn save(&self, w: &mut SnapWriter) {
+        w.u32(self.water.len() as u32);
+        for &v in &self.water {
+            w.f32(v);
+        }
+    }
+
+    pub fn load(&mut self, r: &mut SnapReader) -> Result<(), &'static str> {
+        if r.u32()? as usize != self.water.len() { return Err("snapshot is corrupt"); }
+        for v in &mut self.water {
+            *v = r.f32()?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for Drips {
+    fn default() -> Self {
+        Self::new()
+    }
+}
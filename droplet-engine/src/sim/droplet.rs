@@ -2,121 +2,187 @@
 //
 // Structure-of-Arrays layout for cache-friendly iteration.
 
-use super::{MAX_DROPS, RainWorld, Splashes, Streams};
-use crate::world::{hits_surface, has_flow, get_normal};
-
-// Physics constants
-const GROUND_NEAR: f32 = 1.0;
-const GROUND_FAR: f32 = 0.4;
-const VEL_NEAR: f32 = 1.7;   // Near drops fall fast
-const VEL_FAR: f32 = 0.35;   // Far drops fall slow (perspective)
-const SPLASH_CHANCE: f32 = 0.7;
-const DEPTH_MARGIN: u8 = 48;
-const SLIDE_CHANCE: f32 = 0.6;
-
-pub struct Droplets {
-    // Position
-    pub x: [f32; MAX_DROPS],
-    pub y: [f32; MAX_DROPS],
-    pub z: [f32; MAX_DROPS],  // depth: 0=near, 1=far
-
-    // Velocity (y only, drops fall straight)
-    pub v: [f32; MAX_DROPS],
-
-    // Count
-    pub n: usize,
+use super::{soa_pool, STEP, SEGMENT_CLASSES, Cause, Entities, Impact, RainWorld, SimConfig, Splashes, StepContext};
+use crate::world::{is_sheltered, has_flow, get_material, get_normal_bilinear, get_segment, Material, SplashStyle};
+
+// Physics constants (the tunable ones live in SimConfig)
+const DRIP_VEL: f32 = 0.5;           // drips leave the edge slower than rain
+const SHELTER_REACH: u8 = 64;        // how far back roofs extend (depth units)
+const WIND_DRAG: f32 = 0.1;          // how fast drops pick up the wind, per tick
+
+soa_pool! {
+    pub struct Droplets / Droplet {
+        // Position
+        x: f32,
+        y: f32,
+        z: f32,  // depth: 0=near, 1=far
+
+        // Velocity (v = fall speed, vx = wind drift)
+        v: f32,
+        vx: f32,
+    }
+    extra {
+        // This frame's impacts on the ground line and on surfaces, and both
+        // by the segment class of the pixel hit (for the stats)
+        pub ground_hits: u32 = 0,
+        pub surface_hits: u32 = 0,
+        pub impacts: [u32; SEGMENT_CLASSES] = [0; SEGMENT_CLASSES],
+    }
 }
 
 impl Droplets {
-    pub fn new() -> Self {
-        Self {
-            x: [0.0; MAX_DROPS],
-            y: [0.0; MAX_DROPS],
-            z: [0.0; MAX_DROPS],
-            v: [0.0; MAX_DROPS],
-            n: 0,
+    /// Spawn the step's new drops at top of screen
+    /// With wind, the spawn band extends upwind so drifting rain still covers the screen.
+    pub fn spawn(ctx: &mut StepContext) {
+        let (screen_w, screen_h) = (ctx.w as f32, ctx.h as f32);
+        let (wind, weather, cfg) = (ctx.wind, ctx.weather.params(), ctx.config);
+        let rng = &mut *ctx.rng;
+        let drops = &mut ctx.entities.drops;
+
+        for _ in 0..ctx.spawn_count {
+            let z = RainWorld::rand(rng);
+
+            // Velocity: near drops fall faster (perspective), weather scales it
+            let spread = weather.vel_spread;
+            let v = Self::fall_speed(cfg, z) * weather.vel_scale
+                  * (1.0 - spread + RainWorld::rand(rng) * spread * 2.0);
+            let vx = wind.at(z);
+
+            // Horizontal distance covered before reaching the bottom
+            let drift = vx * screen_h / v;
+            let x = RainWorld::rand(rng) * (screen_w + drift.abs()) - drift.max(0.0);
+
+            drops.push(Droplet { x, y: -RainWorld::rand(rng) * 15.0, z, v, vx });
         }
     }
 
-    pub fn clear(&mut self) {
-        self.n = 0;
+    /// Spawn a single drop at a point (drips from overhangs)
+    pub fn spawn_at(&mut self, x: f32, y: f32, z: f32, cfg: &SimConfig, rng: &mut u32) {
+        let v = Self::fall_speed(cfg, z) * DRIP_VEL * (0.8 + RainWorld::rand(rng) * 0.4);
+        self.push(Droplet { x, y, z, v, vx: 0.0 });
     }
 
-    /// Spawn new drops at top of screen
-    pub fn spawn(&mut self, count: usize, screen_w: f32, rng: &mut u32) {
-        for _ in 0..count {
-            if self.n >= MAX_DROPS { return; }
+    // Base fall speed at depth z (cells per tick)
+    fn fall_speed(cfg: &SimConfig, z: f32) -> f32 {
+        (cfg.vel_near + (cfg.vel_far - cfg.vel_near) * z) * STEP
+    }
 
-            let z = RainWorld::rand(rng);
-            let i = self.n;
+    /// Update drop positions, handle collisions (splashes, streams,
+    /// ripples, drips and impact events come from what they hit)
+    pub fn update(ctx: &mut StepContext) {
+        let (screen_w, screen_h, vp) = (ctx.w as f32, ctx.h as f32, &ctx.viewport);
+        let (pyramid, wind, weather, cfg) = (ctx.pyramid, ctx.wind, ctx.weather.params(), ctx.config);
+        let rng = &mut *ctx.rng;
+        let Entities { drops, splashes, streams, ripples, puddles, drips, impacts: events, .. } = &mut *ctx.entities;
 
-            self.x[i] = RainWorld::rand(rng) * screen_w;
-            self.y[i] = -RainWorld::rand(rng) * 15.0;
-            self.z[i] = z;
+        let (mut ground_hits, mut surface_hits) = (0, 0);
+        let mut impacts = drops.impacts;
 
-            // Velocity: near drops fall faster (perspective)
-            self.v[i] = (VEL_NEAR + (VEL_FAR - VEL_NEAR) * z)
-                      * (0.8 + RainWorld::rand(rng) * 0.4);
+        drops.retain(|e| {
+            let z = e.z;
 
-            self.n += 1;
-        }
-    }
+            // Drift eases toward the wind at this depth
+            let vx = e.vx + (wind.at(z) - e.vx) * WIND_DRAG;
 
-    /// Update drop positions, handle collisions
-    pub fn update(
-        &mut self,
-        screen_w: f32,
-        screen_h: f32,
-        scale_x: f32,
-        scale_y: f32,
-        splashes: &mut Splashes,
-        streams: &mut Streams,
-        rng: &mut u32,
-    ) {
-        let mut write = 0;
-
-        for read in 0..self.n {
-            let x = self.x[read];
-            let y = self.y[read] + self.v[read];
-            let z = self.z[read];
+            let x = e.x + vx;
+            let y_prev = e.y;
+            let y = y_prev + e.v;
 
             // Ground line (perspective)
-            let ground = screen_h * (GROUND_NEAR + (GROUND_FAR - GROUND_NEAR) * z);
+            let ground = cfg.ground(vp, z);
 
             // Convert to background coords
-            let bx = (x * scale_x) as usize;
-            let by = (y * scale_y) as usize;
-
-            // Surface collision (only if on screen)
-            if y >= 0.0 && y < screen_h && x >= 0.0 && x < screen_w {
-                if hits_surface(bx, by, z, DEPTH_MARGIN) {
-                    // Hit a surface - spawn splash biased by surface normal
-                    if has_flow(bx, by) && RainWorld::rand(rng) < SLIDE_CHANCE {
-                        streams.spawn(x, y, z);
+            let bx = vp.col(x);
+            let by = vp.row(y);
+
+            // Surface collision (only if on screen and below the scene's top)
+            if y >= 0.0 && x >= 0.0 && x < screen_w && vp.bg_y(y) >= 0.0 {
+                // Swept: test every background row crossed since last tick,
+                // so fast drops can't tunnel through thin ledges
+                let from = vp.sweep_from(e.x, y_prev, x, y);
+                if let Some(row) = pyramid.sweep(bx, from, by, z, cfg.depth_margin) {
+                    let by = row;
+                    let y = y.min(vp.screen_y((row + 1) as f32));
+
+                    // Feed any overhang this surface drains into
+                    drips.collect(bx, by);
+
+                    surface_hits += 1;
+                    let class = get_segment(bx, by);
+                    if let Some(class) = class {
+                        impacts[class as usize] += 1;
+                    }
+                    events.push(|| {
+                        let normal = get_normal_bilinear(vp.bg_x(x), by as f32 + 0.5);
+                        Impact::new(x, y, z, normal, class, Cause::Surface)
+                    });
+
+                    // Water and puddles ripple instead of splashing
+                    let material = get_material(bx, by);
+                    if material == Material::Water || puddles.is_wet(bx, by) {
+                        ripples.spawn(x, y, z);
+                        return false;
                     }
-                    let (nx, ny) = get_normal(bx, by);
-                    splashes.spawn_with_normal(x, y, z, nx, ny, rng);
-                    continue;
+
+                    // Hit a surface - material decides slide and splash
+                    let mat = material.props();
+                    if has_flow(bx, by) && RainWorld::rand(rng) < mat.slide_chance * cfg.slide_chance {
+                        streams.spawn(x, y, z, cfg);
+                    }
+                    if RainWorld::rand(rng) < mat.splash_chance * weather.splash_scale {
+                        splashes.spawn_with_normal(x, y, z, (vp.bg_x(x), vp.bg_y(y)), mat.style, rng);
+                    }
+                    return false;
+                }
+
+                // Under a roof - it would have landed on top, so nothing below gets wet
+                if y < screen_h && is_sheltered(bx, by, z, SHELTER_REACH) {
+                    return false;
                 }
             }
 
             // Ground collision
             if y > ground {
-                if RainWorld::rand(rng) < SPLASH_CHANCE {
-                    let typ = (RainWorld::rand(rng) * 4.0) as u8;
+                // Blown off screen - nothing to show
+                if x < 0.0 || x >= screen_w { return false; }
+
+                let gy = vp.row(ground);
+                ground_hits += 1;
+                let class = get_segment(bx, gy);
+                if let Some(class) = class {
+                    impacts[class as usize] += 1;
+                }
+                events.push(|| {
+                    let normal = get_normal_bilinear(vp.bg_x(x), gy as f32 + 0.5);
+                    Impact::new(x, ground, z, normal, class, Cause::Ground)
+                });
+
+                let material = get_material(bx, gy);
+                if material == Material::Water || puddles.is_wet(bx, gy) {
+                    ripples.spawn(x, ground, z);
+                    return false;
+                }
+
+                let mat = material.props();
+                if RainWorld::rand(rng) < cfg.splash_chance * mat.splash_chance * weather.splash_scale {
+                    let typ = match mat.style {
+                        SplashStyle::Normal => (RainWorld::rand(rng) * 4.0) as u8,
+                        style => Splashes::style_type(style, 0.0, rng),
+                    };
                     splashes.spawn(x, ground, z, typ, rng);
                 }
-                continue;
+                return false;
             }
 
             // Keep falling
-            self.x[write] = x;
-            self.y[write] = y;
-            self.z[write] = z;
-            self.v[write] = self.v[read];
-            write += 1;
-        }
-
-        self.n = write;
+            e.x = x;
+            e.y = y;
+            e.vx = vx;
+            true
+        });
+
+        drops.ground_hits += ground_hits;
+        drops.surface_hits += surface_hits;
+        drops.impacts = impacts;
     }
 }
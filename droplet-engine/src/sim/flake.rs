@@ -0,0 +1,108 @@
+// flake.rs - Falling snowflakes
+//
+// Slow fall with a sinusoidal sway. Flakes that land settle into the
+// snow layer instead of splashing.
+
+use super::{soa_pool, STEP, RainWorld, SimConfig, Snow, Viewport, Wind};
+use crate::world::{hits_surface, is_sheltered, get_normal};
+use crate::math;
+
+const VEL_NEAR: f32 = 21.0 * STEP;   // flakes fall far slower than rain (cells/s)
+const VEL_FAR: f32 = 6.0 * STEP;
+const SWAY_AMP: f32 = 15.0 * STEP;   // peak lateral sway speed (near, cells/s)
+const SWAY_FREQ: f32 = 3.6 * STEP;   // rad/s (randomized +/-50%)
+const SHELTER_REACH: u8 = 64;
+
+soa_pool! {
+    pub struct Flakes / Flake {
+        // Position
+        x: f32,
+        y: f32,
+        z: f32,
+
+        // Motion
+        v: f32,      // fall speed
+        vx: f32,     // last step's sideways move, sway + wind (for interpolation)
+        phase: f32,  // sway phase (radians)
+        freq: f32,   // sway speed
+    }
+}
+
+impl Flakes {
+    /// Spawn new flakes at top of screen (band widened upwind like drops)
+    pub fn spawn(&mut self, count: usize, screen_w: f32, screen_h: f32, wind: &Wind, rng: &mut u32) {
+        for _ in 0..count {
+            if self.is_full() { return; }
+
+            let z = RainWorld::rand(rng);
+            let v = (VEL_NEAR + (VEL_FAR - VEL_NEAR) * z) * (0.8 + RainWorld::rand(rng) * 0.4);
+            let drift = wind.at(z) * screen_h / v;
+
+            self.push(Flake {
+                x: RainWorld::rand(rng) * (screen_w + drift.abs()) - drift.max(0.0),
+                y: -RainWorld::rand(rng) * 15.0,
+                z,
+                v,
+                vx: 0.0,
+                phase: RainWorld::rand(rng) * std::f32::consts::TAU,
+                freq: SWAY_FREQ * (0.5 + RainWorld::rand(rng)),
+            });
+        }
+    }
+
+    /// Drift and fall; landed flakes settle into the snow layer
+    pub fn update(
+        &mut self,
+        screen_w: f32,
+        screen_h: f32,
+        vp: &Viewport,
+        snow: &mut Snow,
+        wind: &Wind,
+        cfg: &SimConfig,
+    ) {
+        self.retain(|e| {
+            let z = e.z;
+            e.phase += e.freq;
+
+            // Sway is smaller for far flakes (perspective)
+            let sway = math::sin(e.phase) * SWAY_AMP * (1.0 - z * 0.6);
+            let x = e.x + sway + wind.at(z);
+            let y = e.y + e.v;
+
+            let ground = cfg.ground(vp, z);
+
+            let bx = vp.col(x);
+            let by = vp.row(y);
+
+            if y >= 0.0 && y < screen_h && x >= 0.0 && x < screen_w {
+                // Caught by a roof
+                if is_sheltered(bx, by, z, SHELTER_REACH) {
+                    return false;
+                }
+
+                // Landed on a surface
+                if hits_surface(bx, by, z, cfg.depth_margin) {
+                    let (nx, ny) = get_normal(bx, by);
+                    snow.settle(bx, by, nx, ny);
+                    return false;
+                }
+            }
+
+            // Reached the ground line
+            if y > ground {
+                if x >= 0.0 && x < screen_w {
+                    let gy = vp.row(ground);
+                    let (nx, ny) = get_normal(bx, gy);
+                    snow.settle(bx, gy, nx, ny);
+                }
+                return false;
+            }
+
+            // Keep falling
+            e.vx = x - e.x;
+            e.x = x;
+            e.y = y;
+            true
+        });
+    }
+}
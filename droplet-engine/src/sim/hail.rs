@@ -0,0 +1,139 @@
+// hail.rs - Bouncing hailstones
+//
+// Ballistic 2D particles: gravity, both velocity components, and a
+// reflection off the surface normal on impact. Repeated small bounces on a
+// slope turn into rolling. Stones melt away a while after first contact.
+
+use super::{soa_pool, STEP, ticks, RainWorld, SimConfig, Viewport, Wind};
+use crate::world::{is_sheltered, get_normal_bilinear, DepthPyramid};
+
+const VEL_NEAR: f32 = 84.0 * STEP;         // initial fall speed (cells/s)
+const VEL_FAR: f32 = 24.0 * STEP;
+const GRAVITY: f32 = 216.0 * STEP * STEP;  // cells/s^2
+const RESTITUTION: f32 = 0.45;             // normal speed kept after a bounce
+const FRICTION: f32 = 0.92;                // tangential speed kept per contact
+const ROLL_SPEED: f32 = 9.0 * STEP;        // normal speed below which contact counts as rolling
+const MELT_TICKS: u16 = ticks(4.0) as u16; // lifetime after first contact
+const SHELTER_REACH: u8 = 64;
+
+// Display states (encoded as the variant)
+pub const HAIL_FALLING: u8 = 0;
+pub const HAIL_BOUNCING: u8 = 1;
+pub const HAIL_ROLLING: u8 = 2;
+pub const HAIL_MELTING: u8 = 3;
+
+soa_pool! {
+    pub struct Hail / Hailstone {
+        // Position
+        x: f32,
+        y: f32,
+        z: f32,
+
+        // Velocity
+        vx: f32,
+        vy: f32,
+
+        // Lifetime (0 = not landed yet)
+        melt: u16,
+        state: u8,
+    }
+}
+
+impl Hail {
+    /// Spawn new hailstones at top of screen
+    pub fn spawn(&mut self, count: usize, screen_w: f32, wind: &Wind, rng: &mut u32) {
+        for _ in 0..count {
+            if self.is_full() { return; }
+
+            let z = RainWorld::rand(rng);
+            self.push(Hailstone {
+                x: RainWorld::rand(rng) * screen_w,
+                y: -RainWorld::rand(rng) * 10.0,
+                z,
+                vx: wind.at(z),
+                vy: (VEL_NEAR + (VEL_FAR - VEL_NEAR) * z) * (0.9 + RainWorld::rand(rng) * 0.2),
+                melt: 0,
+                state: HAIL_FALLING,
+            });
+        }
+    }
+
+    /// Integrate, bounce off surfaces and the ground line, melt
+    pub fn update(
+        &mut self,
+        screen_w: f32,
+        screen_h: f32,
+        vp: &Viewport,
+        pyramid: &DepthPyramid,
+        cfg: &SimConfig,
+    ) {
+        self.retain(|e| {
+            let z = e.z;
+
+            // Melting after first contact
+            if e.melt > 0 {
+                e.melt -= 1;
+                if e.melt == 0 { return false; }
+            }
+
+            let mut vx = e.vx;
+            let mut vy = e.vy + GRAVITY * (1.0 - z * 0.6);
+
+            let y_prev = e.y;
+            let x = e.x + vx;
+            let mut y = y_prev + vy;
+
+            if x < 0.0 || x >= screen_w { return false; }
+
+            let ground = cfg.ground(vp, z);
+
+            // Find what it hit this tick (surfaces only stop downward motion)
+            let mut normal = None;
+            if vy > 0.0 && y >= 0.0 && vp.bg_y(y) >= 0.0 {
+                let bx = vp.col(x);
+                let by = vp.row(y);
+                let from = vp.sweep_from(e.x, y_prev, x, y);
+
+                if let Some(row) = pyramid.sweep(bx, from, by, z, cfg.depth_margin) {
+                    normal = Some(get_normal_bilinear(vp.bg_x(x), row as f32 + 0.5));
+                } else if y < screen_h && e.melt == 0 && is_sheltered(bx, by, z, SHELTER_REACH) {
+                    // Stopped by a roof above
+                    return false;
+                }
+            }
+            if normal.is_none() && y > ground {
+                normal = Some((0.0, -1.0));
+            }
+
+            if let Some((nx, ny)) = normal {
+                // Pixels facing the camera carry no slope - treat as level
+                let len = (nx * nx + ny * ny).sqrt();
+                let (nx, ny) = if len < 0.1 { (0.0, -1.0) } else { (nx / len, ny / len) };
+
+                // Reflect: split into normal and tangential parts
+                let vn = vx * nx + vy * ny;
+                if vn < 0.0 {
+                    let (tx, ty) = (vx - vn * nx, vy - vn * ny);
+                    vx = tx * FRICTION - vn * RESTITUTION * nx;
+                    vy = ty * FRICTION - vn * RESTITUTION * ny;
+                }
+
+                // Back above the surface; keep the sideways move so it rolls
+                y = y_prev.min(ground);
+
+                if e.melt == 0 { e.melt = MELT_TICKS; }
+                e.state = if vn.abs() < ROLL_SPEED { HAIL_ROLLING } else { HAIL_BOUNCING };
+            }
+
+            if e.melt > 0 && e.melt < MELT_TICKS / 4 {
+                e.state = HAIL_MELTING;
+            }
+
+            e.x = x;
+            e.y = y;
+            e.vx = vx;
+            e.vy = vy;
+            true
+        });
+    }
+}
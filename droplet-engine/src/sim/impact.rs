@@ -0,0 +1,123 @@
+// impact.rs - Impact events for audio and effects
+//
+// Drops report where they land and streams where they end, so the front-end
+// can react to single impacts (spatial audio, haptics) instead of reading
+// them back out of the rasterized buffer. The queue holds the events of the
+// last tick / tick_dt call; it is cleared when the next one starts.
+//
+// Off by default. `cap` bounds the events kept per frame (extra ones are
+// only counted) and `rate` keeps a random fraction of them, drawn from the
+// queue's own RNG stream so sampling never shifts the simulation. Normals
+// are only sampled for events that are kept.
+//
+// Impact is #[repr(C)], 24 bytes: five f32 then two u8, so JS reads the
+// queue in place - a Float32Array with a stride of 6 for the floats and a
+// Uint8Array with a stride of 24 for class and kind.
+
+use super::{rng, Cause, RainWorld, SnapReader, SnapWriter};
+
+/// Segment class of an impact outside the scene
+pub const NO_CLASS: u8 = 255;
+
+pub const MAX_IMPACT_CAP: usize = 1 << 16;
+
+/// One impact
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Impact {
+    pub x: f32,      // screen cells
+    pub y: f32,
+    pub z: f32,      // depth: 0=near, 1=far
+    pub nx: f32,     // surface normal (background space)
+    pub ny: f32,
+    pub class: u8,   // ADE20K class of the pixel (NO_CLASS off the scene)
+    pub kind: u8,    // Cause: ground, surface, stream fall, pool, or other (stream ran dry)
+    _pad: [u8; 2],
+}
+
+impl Impact {
+    pub fn new(x: f32, y: f32, z: f32, (nx, ny): (f32, f32), class: Option<u8>, kind: Cause) -> Self {
+        Self { x, y, z, nx, ny, class: class.unwrap_or(NO_CLASS), kind: kind as u8, _pad: [0; 2] }
+    }
+}
+
+pub struct Impacts {
+    events: Vec<Impact>,
+    cap: usize,
+    rate: f32,
+    rng: u32,
+
+    // Events past the cap this frame
+    pub overflow: u32,
+}
+
+impl Impacts {
+    pub fn new() -> Self {
+        Self {
+            events: Vec::new(),
+            cap: 0,
+            rate: 1.0,
+            rng: rng::stream(rng::DEFAULT_SEED, rng::IMPACT),
+            overflow: 0,
+        }
+    }
+
+    /// Keep up to `cap` events per frame (0 = off), sampled at `rate` (0-1)
+    pub fn configure(&mut self, cap: usize, rate: f32) {
+        self.cap = cap.min(MAX_IMPACT_CAP);
+        self.rate = if rate.is_nan() { 1.0 } else { rate.clamp(0.0, 1.0) };
+        self.events = Vec::with_capacity(self.cap);
+        self.overflow = 0;
+    }
+
+    pub fn cap(&self) -> usize { self.cap }
+    pub fn rate(&self) -> f32 { self.rate }
+
+    /// Restart the sampling RNG
+    pub fn seed(&mut self, state: u32) {
+        self.rng = state;
+    }
+
+    /// Start a frame
+    pub fn clear(&mut self) {
+        self.events.clear();
+        self.overflow = 0;
+    }
+
+    /// Record an impact if it's sampled and fits; `make` only runs then
+    #[inline]
+    pub fn push(&mut self, make: impl FnOnce() -> Impact) {
+        if self.cap == 0 { return; }
+        if self.rate < 1.0 && RainWorld::rand(&mut self.rng) >= self.rate { return; }
+        if self.events.len() >= self.cap {
+            self.overflow = self.overflow.saturating_add(1);
+            return;
+        }
+        self.events.push(make());
+    }
+
+    pub fn events(&self) -> &[Impact] {
+        &self.events
+    }
+
+    pub fn save(&self, w: &mut SnapWriter) {
+        w.u32(self.cap as u32);
+        w.f32(self.rate);
+        w.u32(self.rng);
+    }
+
+    pub fn load(&mut self, r: &mut SnapReader) -> Result<(), &'static str> {
+        let cap = r.u32()? as usize;
+        let rate = r.f32()?;
+        if cap > MAX_IMPACT_CAP || !(0.0..=1.0).contains(&rate) { return Err("snapshot is corrupt"); }
+        self.configure(cap, rate);
+        self.rng = r.rng()?;
+        Ok(())
+    }
+}
+
+impl Default for Impacts {
+    fn default() -> Self {
+        Self::new()
+    }
+}
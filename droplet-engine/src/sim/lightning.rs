@@ -0,0 +1,173 @@
+// lightning.rs - Lightning strikes
+//
+// Strikes get more frequent as weather intensity rises. Each strike grows a
+// jagged bolt from the top of the screen down to the first solid pixel of a
+// random scene column, with short side branches. The bolt stays on screen
+// for a few frames; a global flash value decays after it for the front-end
+// to brighten the background. Timing and shape draw from the lightning
+// system's RNG stream, so a storm plays out the same way for a given seed.
+
+use super::{RainWorld, SnapReader, SnapWriter, Viewport, TICK_RATE, ticks};
+use crate::scene::{BG_WIDTH, BG_HEIGHT};
+use crate::world::{get_depth_raw, SKY_DEPTH};
+
+pub const MAX_BOLT_CELLS: usize = 1024;
+
+const STRIKE_MIN: f32 = 0.7;                   // intensity below which nothing strikes
+const INTERVAL_MAX: f32 = 15.0 * TICK_RATE;    // mean ticks between strikes at STRIKE_MIN (15 s)
+const INTERVAL_MIN: f32 = 3.0 * TICK_RATE;     // ... and at full intensity (3 s)
+const BOLT_FRAMES: u8 = ticks(0.2) as u8;      // ticks a bolt stays visible
+const FLASH_DECAY: f32 = 0.85;                 // flash kept per tick
+const FLICKER_FRAME: u8 = ticks(0.067) as u8;  // return stroke re-brightens the flash
+const BRANCH_CHANCE: f32 = 0.08;               // per main-channel cell
+const BRANCH_LEN_MIN: u32 = 4;
+const BRANCH_LEN_MAX: u32 = 16;
+
+pub struct Lightning {
+    // Bolt cells (screen space)
+    pub x: [i16; MAX_BOLT_CELLS],
+    pub y: [i16; MAX_BOLT_CELLS],
+    pub branch: [bool; MAX_BOLT_CELLS],
+    pub n: usize,
+
+    // Frames since the strike (>= BOLT_FRAMES = no bolt)
+    pub age: u8,
+
+    flash: f32,
+}
+
+impl Lightning {
+    pub fn new() -> Self {
+        Self {
+            x: [0; MAX_BOLT_CELLS],
+            y: [0; MAX_BOLT_CELLS],
+            branch: [false; MAX_BOLT_CELLS],
+            n: 0,
+            age: BOLT_FRAMES,
+            flash: 0.0,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.n = 0;
+        self.age = BOLT_FRAMES;
+    }
+
+    /// Global flash brightness (0-1)
+    pub fn flash(&self) -> f32 {
+        self.flash
+    }
+
+    /// Fade 0-3 of the current bolt (0 = fresh)
+    #[inline(always)]
+    pub fn fade(&self) -> u8 {
+        (self.age as u32 * 4 / BOLT_FRAMES as u32).min(3) as u8
+    }
+
+    /// Bolt visible this frame
+    pub fn visible(&self) -> bool {
+        self.age < BOLT_FRAMES && self.n > 0
+    }
+
+    /// Age the current bolt and maybe strike
+    pub fn update(&mut self, intensity: f32, screen_w: u32, screen_h: u32, vp: &Viewport, rng: &mut u32) {
+        self.flash *= FLASH_DECAY;
+        if self.age < BOLT_FRAMES {
+            self.age += 1;
+            if self.age == FLICKER_FRAME {
+                self.flash = self.flash.max(0.7);
+            }
+        }
+
+        if intensity < STRIKE_MIN { return; }
+
+        // Chance per tick from the mean interval at this intensity
+        let t = ((intensity - STRIKE_MIN) / (1.0 - STRIKE_MIN)).min(1.0);
+        let interval = INTERVAL_MAX + (INTERVAL_MIN - INTERVAL_MAX) * t;
+        if RainWorld::rand(rng) < 1.0 / interval {
+            self.strike(screen_w, screen_h, vp, rng);
+        }
+    }
+
+    /// Grow a new bolt toward a random visible scene column
+    pub fn strike(&mut self, screen_w: u32, screen_h: u32, vp: &Viewport, rng: &mut u32) {
+        let (w, h) = (screen_w as i32, screen_h as i32);
+        if w == 0 || h == 0 { return; }
+
+        // Target: first solid pixel from the top of a random column
+        let c0 = vp.bg_x(0.0).max(0.0);
+        let c1 = vp.bg_x(screen_w as f32).min(BG_WIDTH as f32);
+        let bx = ((c0 + RainWorld::rand(rng) * (c1 - c0)) as usize).min(BG_WIDTH - 1);
+        let by = (0..BG_HEIGHT).find(|&y| get_depth_raw(bx, y) > SKY_DEPTH).unwrap_or(BG_HEIGHT - 1);
+        let tx = (vp.screen_x(bx as f32) as i32).clamp(0, w - 1);
+        let ty = (vp.screen_y(by as f32) as i32).min(h - 1).max(1);
+
+        self.n = 0;
+        self.age = 0;
+        self.flash = 1.0;
+
+        // Main channel: one cell per row, jittering toward the target
+        let mut x = tx + ((RainWorld::rand(rng) - 0.5) * ty as f32 * 0.5) as i32;
+        for y in 0..=ty {
+            let rows_left = (ty - y).max(1) as f32;
+            let pull = (tx - x) as f32 / rows_left;
+            let step = ((RainWorld::rand(rng) * 3.0) as i32 - 1 + pull.round() as i32).clamp(-2, 2);
+            x = (x + step).clamp(0, w - 1);
+            self.push(x, y, false);
+
+            // Side branch heading away and down
+            if y < ty && RainWorld::rand(rng) < BRANCH_CHANCE {
+                let dir = if RainWorld::rand(rng) < 0.5 { -1 } else { 1 };
+                let span = (BRANCH_LEN_MAX - BRANCH_LEN_MIN) as f32;
+                let len = BRANCH_LEN_MIN + (RainWorld::rand(rng) * span) as u32;
+                let (mut bx, mut by) = (x, y);
+                for _ in 0..len {
+                    bx += dir * (RainWorld::rand(rng) * 2.0) as i32;
+                    by += 1;
+                    if bx < 0 || bx >= w || by >= h { break; }
+                    self.push(bx, by, true);
+                }
+            }
+        }
+    }
+
+    /// Bolt cells, age and flash
+    pub fn save(&self, w: &mut SnapWriter) {
+        w.u32(self.n as u32);
+        for i in 0..self.n {
+            w.u16(self.x[i] as u16);
+            w.u16(self.y[i] as u16);
+            w.bool(self.branch[i]);
+        }
+        w.u8(self.age);
+        w.f32(self.flash);
+    }
+
+    pub fn load(&mut self, r: &mut SnapReader) -> Result<(), &'static str> {
+        let n = r.u32()? as usize;
+        if n > MAX_BOLT_CELLS { return Err("snapshot is corrupt"); }
+        for i in 0..n {
+            self.x[i] = r.u16()? as i16;
+            self.y[i] = r.u16()? as i16;
+            self.branch[i] = r.bool()?;
+        }
+        self.n = n;
+        self.age = r.u8()?;
+        self.flash = r.f32()?;
+        Ok(())
+    }
+
+    fn push(&mut self, x: i32, y: i32, branch: bool) {
+        if self.n >= MAX_BOLT_CELLS { return; }
+        self.x[self.n] = x as i16;
+        self.y[self.n] = y as i16;
+        self.branch[self.n] = branch;
+        self.n += 1;
+    }
+}
+
+impl Default for Lightning {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,23 +1,132 @@
 // sim/ - Rain simulation
 //
 // Entity management using Structure-of-Arrays for cache efficiency.
-// Each entity type in its own module.
+// Each entity type in its own module; each is stepped and encoded by a
+// system (system.rs), so custom effects plug in without touching tick().
 
+mod pool;
 mod droplet;
 mod splash;
+mod spray;
 mod stream;
+mod ripple;
+mod puddle;
+mod drip;
+mod wind;
+mod weather;
+mod flake;
+mod snow;
+mod hail;
+mod lightning;
+mod rng;
+mod config;
+mod viewport;
+mod system;
+mod builtin;
+mod snapshot;
+mod replay;
+mod stats;
+mod impact;
 
-pub use droplet::Droplets;
-pub use splash::Splashes;
-pub use stream::Streams;
+pub use droplet::{Droplets, Droplet};
+pub use splash::{Splashes, Splash, SplashMode, SPLASH_PUFF};
+pub use spray::{Spray, SprayDrop};
+pub use stream::{Streams, Stream};
+pub use ripple::{Ripples, Ripple};
+pub use puddle::Puddles;
+pub use drip::Drips;
+pub use wind::Wind;
+pub use weather::{Weather, WeatherParams, WeatherState};
+pub use flake::{Flakes, Flake};
+pub use snow::Snow;
+pub use hail::{Hail, Hailstone};
+pub use lightning::Lightning;
+pub use config::{SimConfig, ConfigPreset};
+pub use viewport::{Viewport, ViewportMode};
+pub use system::{System, Systems, Entities, StepContext, RenderContext};
+pub use system::{ORDER_LIGHTNING, ORDER_SPAWN, ORDER_DROPS, ORDER_SPLASHES, ORDER_STREAMS, ORDER_RIPPLES};
+pub use system::{ORDER_FLAKES, ORDER_HAIL, ORDER_PUDDLES, ORDER_SNOW};
+pub use snapshot::{SnapWriter, SnapReader, SnapField};
+pub use replay::{replay, checksum, ReplayReport};
+pub use stats::{Stats, Kind, Cause, KINDS, KIND_NAMES, CAUSES, CAUSE_NAMES};
+pub use impact::{Impact, Impacts, NO_CLASS, MAX_IMPACT_CAP};
 
-use crate::scene::{BG_WIDTH, BG_HEIGHT};
+use pool::soa_pool;
+use replay::{Event, Recorder};
+use stats::Profiler;
 use crate::render::Encoder;
+use crate::sprite::SpriteSheet;
+use crate::world::DepthPyramid;
 
-// Capacity limits
+// Fixed simulation step. Tuning constants are written in seconds (speeds in
+// cells per second) and converted to per-tick values with STEP and ticks().
+pub const TICK_RATE: f32 = 60.0;
+pub const STEP: f32 = 1.0 / TICK_RATE;
+const MAX_FRAME_TIME: f32 = 0.25;  // longer frames are clamped (tab in background)
+const MAX_STEPS: u32 = 8;          // steps per tick_dt call before dropping time
+
+/// Whole ticks in a duration (rounded)
+pub const fn ticks(seconds: f32) -> u32 {
+    (seconds * TICK_RATE + 0.5) as u32
+}
+
+// Capacity limits (drops, splashes and streams can be resized at runtime;
+// these are their defaults)
 pub const MAX_DROPS: usize = 3000;
 pub const MAX_SPLASHES: usize = 200;
+pub const MAX_SPRAY: usize = 1000;
 pub const MAX_STREAMS: usize = 500;
+pub const MAX_POOL: usize = 1 << 20;  // upper bound for any resizable pool
+pub const MAX_RIPPLES: usize = 300;
+pub const MAX_FLAKES: usize = 2000;
+pub const MAX_HAIL: usize = 500;
+
+// ADE20K class IDs fit in a byte (impact stats are kept per class)
+pub const SEGMENT_CLASSES: usize = 256;
+
+/// What falls from the sky
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(u8)]
+pub enum Precipitation {
+    Rain = 0,
+    Snow = 1,
+    Hail = 2,
+}
+
+impl Precipitation {
+    pub fn from_u8(v: u8) -> Option<Self> {
+        match v {
+            0 => Some(Self::Rain),
+            1 => Some(Self::Snow),
+            2 => Some(Self::Hail),
+            _ => None,
+        }
+    }
+}
+
+/// Capacities of the resizable entity pools
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct PoolSizes {
+    pub drops: usize,
+    pub splashes: usize,
+    pub streams: usize,
+}
+
+impl PoolSizes {
+    pub const DEFAULT: Self = Self {
+        drops: MAX_DROPS,
+        splashes: MAX_SPLASHES,
+        streams: MAX_STREAMS,
+    };
+
+    fn clamped(self) -> Self {
+        Self {
+            drops: self.drops.min(MAX_POOL),
+            splashes: self.splashes.min(MAX_POOL),
+            streams: self.streams.min(MAX_POOL),
+        }
+    }
+}
 
 /// Rain simulation world
 pub struct RainWorld {
@@ -25,79 +134,421 @@ pub struct RainWorld {
     w: u32,
     h: u32,
 
-    // Precomputed scale factors (screen -> background)
-    scale_x: f32,
-    scale_y: f32,
+    // Where the scene sits on screen (all screen <-> background conversions)
+    viewport: Viewport,
+
+    // Tunable parameters
+    config: SimConfig,
 
-    // Entities
-    drops: Droplets,
-    splashes: Splashes,
-    streams: Streams,
+    // Resize clears entities instead of moving them to the new screen
+    reset_on_resize: bool,
+
+    // Entity pools and dynamic layers, and the systems that run them
+    entities: Entities,
+    systems: Systems,
+
+    // Environment
+    wind: Wind,
+    weather: Weather,
+    precipitation: Precipitation,
+
+    // Static lookups
+    pyramid: DepthPyramid,
 
     // Output
     encoder: Encoder,
 
-    // RNG state
-    rng: u32,
+    // Fixed-step timing: unsimulated time, and how far into the next step
+    // the last render was (1 = exactly on a step)
+    accumulator: f32,
+    alpha: f32,
+
+    // RNG seed (streams live in the systems and subsystems)
+    seed: u32,
+
+    // Input log while recording (see replay.rs)
+    recorder: Option<Recorder>,
+
+    // Per-phase timing for the stats
+    profiler: Profiler,
 }
 
 impl RainWorld {
     pub fn new(w: u32, h: u32) -> Self {
+        Self::with_capacity(w, h, PoolSizes::DEFAULT)
+    }
+
+    pub fn with_capacity(w: u32, h: u32, sizes: PoolSizes) -> Self {
+        let sizes = sizes.clamped();
+        let mut systems = Systems::new();
+        builtin::register(&mut systems, rng::DEFAULT_SEED);
+
         Self {
             w,
             h,
-            scale_x: BG_WIDTH as f32 / w as f32,
-            scale_y: BG_HEIGHT as f32 / h as f32,
-            drops: Droplets::new(),
-            splashes: Splashes::new(),
-            streams: Streams::new(),
+            viewport: Viewport::new(w, h),
+            config: SimConfig::DEFAULT,
+            reset_on_resize: false,
+            entities: Entities::new(sizes.drops, sizes.splashes, sizes.streams, MAX_RIPPLES, MAX_FLAKES, MAX_HAIL),
+            systems,
+            wind: Wind::new(),
+            weather: Weather::new(),
+            precipitation: Precipitation::Rain,
+            pyramid: DepthPyramid::new(),
             encoder: Encoder::new(w, h),
-            rng: 0xDEADBEEF,
+            accumulator: 0.0,
+            alpha: 1.0,
+            seed: rng::DEFAULT_SEED,
+            recorder: None,
+            profiler: Profiler::default(),
         }
     }
 
+    /// Change the screen size. Live entities keep their place in the scene
+    /// unless reset_on_resize is set.
     pub fn resize(&mut self, w: u32, h: u32) {
+        self.record(Event::Resize(w, h));
+        let from = self.viewport;
+        let keep = !self.reset_on_resize && self.w > 0 && self.h > 0;
+
         self.w = w;
         self.h = h;
-        self.scale_x = BG_WIDTH as f32 / w as f32;
-        self.scale_y = BG_HEIGHT as f32 / h as f32;
+        self.viewport.fit(w, h);
         self.encoder.resize(w, h);
-        self.drops.clear();
-        self.splashes.clear();
-        self.streams.clear();
+        self.remap(from, keep);
+    }
+
+    /// How the scene is fitted to the screen; anchors (0-1) place it when
+    /// it's cropped or letterboxed. Live entities stay on their scene spot.
+    pub fn set_viewport(&mut self, mode: ViewportMode, anchor_x: f32, anchor_y: f32) {
+        self.record(Event::Viewport(mode, anchor_x, anchor_y));
+        let from = self.viewport;
+        self.viewport.set_mode(mode, anchor_x, anchor_y, self.w, self.h);
+        self.remap(from, true);
+    }
+
+    /// Width / height of a screen cell in pixels (cover and contain keep
+    /// the scene's aspect ratio in pixels, not cells)
+    pub fn set_cell_aspect(&mut self, aspect: f32) {
+        self.record(Event::CellAspect(aspect));
+        let from = self.viewport;
+        self.viewport.set_cell_aspect(aspect, self.w, self.h);
+        self.remap(from, true);
+    }
+
+    // Move entities from one mapping to the current one, or drop them
+    fn remap(&mut self, from: Viewport, keep: bool) {
+        let keep = keep && self.w > 0 && self.h > 0;
+        for (system, _) in self.systems.iter_mut() {
+            if keep {
+                system.rescale(&mut self.entities, &from, &self.viewport);
+            } else {
+                system.reset(&mut self.entities);
+            }
+        }
     }
 
+    /// Advance one fixed 1/60 s step and render it
     pub fn tick(&mut self) {
+        self.begin_frame();
+        self.step();
+        self.alpha = 1.0;
+        self.render();
+        if let Some(r) = &mut self.recorder {
+            r.frame(None, self.encoder.as_slice());
+        }
+    }
+
+    /// Advance by real elapsed time: whole fixed steps, then render
+    /// interpolated between the last two steps
+    pub fn tick_dt(&mut self, seconds: f32) {
+        self.begin_frame();
+        self.accumulator += seconds.clamp(0.0, MAX_FRAME_TIME);
+
+        let mut steps = 0;
+        while self.accumulator >= STEP {
+            if steps == MAX_STEPS {
+                // Can't keep up - drop the backlog rather than spiral
+                self.accumulator = 0.0;
+                break;
+            }
+            self.step();
+            self.accumulator -= STEP;
+            steps += 1;
+        }
+
+        self.alpha = self.accumulator / STEP;
+        self.render();
+        if let Some(r) = &mut self.recorder {
+            r.frame(Some(seconds), self.encoder.as_slice());
+        }
+    }
+
+    // Start the per-frame stats, timings and impact queue
+    fn begin_frame(&mut self) {
+        self.profiler.begin(self.systems.len() + 2);
+        self.entities.reset_counts();
+        self.entities.impacts.clear();
+    }
+
+    fn step(&mut self) {
+        // Environment
+        self.weather.update();
+        self.wind.set_boost(self.weather.params().wind);
+        self.wind.update();
+        self.entities.splashes.wind = self.wind.current();
+        self.entities.splashes.frames = self.config.splash_frames();
+        let spawn_count = self.weather.spawn_count(((self.w >> 6) + 1) as usize);
+        self.profiler.lap(0);
+
+        // Systems, in order
+        for (i, (system, rng)) in self.systems.iter_mut().enumerate() {
+            let mut ctx = StepContext {
+                w: self.w,
+                h: self.h,
+                viewport: self.viewport,
+                config: &self.config,
+                wind: &self.wind,
+                weather: &self.weather,
+                precipitation: self.precipitation,
+                spawn_count,
+                pyramid: &self.pyramid,
+                entities: &mut self.entities,
+                rng,
+            };
+            system.update(&mut ctx);
+            self.profiler.lap(i + 1);
+        }
+    }
+
+    // Encode the current state to the output buffer
+    fn render(&mut self) {
+        let ctx = RenderContext {
+            w: self.w,
+            h: self.h,
+            viewport: self.viewport,
+            alpha: self.alpha,
+            entities: &self.entities,
+        };
+
         self.encoder.clear();
+        for system in self.systems.iter() {
+            system.render(&ctx, &mut self.encoder);
+        }
+        self.profiler.lap(self.systems.len() + 1);
+    }
+
+    /// Drop every live entity (puddles, drips and settled snow stay)
+    pub fn clear(&mut self) {
+        self.record(Event::Clear);
+        for (system, _) in self.systems.iter_mut() {
+            system.reset(&mut self.entities);
+        }
+        self.render();
+    }
+
+    /// Counts and, while profiling, per-phase timing for the last tick /
+    /// tick_dt call, over all the steps it ran (see stats.rs)
+    pub fn stats(&self) -> Stats {
+        let phases = match self.profiler.enabled() {
+            true => self.phase_names().zip(self.profiler.ms().iter().copied()).collect(),
+            false => Vec::new(),
+        };
+        Stats::collect(&self.entities, phases)
+    }
+
+    /// Phases timed by the profiler: "environment", each system, "render"
+    pub fn phase_names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        std::iter::once("environment")
+            .chain(self.systems.list().map(|(name, _)| name))
+            .chain(std::iter::once("render"))
+    }
+
+    /// Measure per-phase wall time (off by default)
+    pub fn set_profiling(&mut self, enabled: bool) {
+        self.profiler.set_enabled(enabled);
+    }
+
+    /// Queue up to `cap` impact events per frame (0 = off, the default),
+    /// keeping a random `sample_rate` (0-1) of them (see impact.rs)
+    pub fn set_impact_events(&mut self, cap: usize, sample_rate: f32) {
+        self.record(Event::ImpactEvents(cap as u32, sample_rate));
+        self.entities.impacts.configure(cap, sample_rate);
+    }
+
+    /// Impacts of the last tick / tick_dt call, in the order they happened
+    pub fn impacts(&self) -> &[Impact] {
+        self.entities.impacts.events()
+    }
+
+    /// Impacts past the cap in the last tick / tick_dt call
+    pub fn impact_overflow(&self) -> u32 {
+        self.entities.impacts.overflow
+    }
+
+    /// Base wind strength in screen cells per second (+ = right)
+    pub fn set_wind(&mut self, strength: f32) {
+        self.record(Event::Wind(strength));
+        self.wind.set_strength(strength);
+    }
+
+    /// Extra drift for far drops (0 = same at all depths)
+    pub fn set_wind_depth_drift(&mut self, drift: f32) {
+        self.record(Event::WindDepthDrift(drift));
+        self.wind.set_depth_drift(drift);
+    }
+
+    /// Blend to a weather state over `seconds` (0 = instant)
+    pub fn set_weather(&mut self, state: WeatherState, seconds: f32) {
+        self.record(Event::Weather(state, seconds));
+        self.weather.set_state(state, ticks(seconds.max(0.0)));
+    }
+
+    /// Let the weather wander between states on its own
+    pub fn set_weather_auto(&mut self, auto: bool) {
+        self.record(Event::WeatherAuto(auto));
+        self.weather.set_auto(auto);
+    }
+
+    /// Clear entities on resize instead of rescaling them (the old behaviour)
+    pub fn set_reset_on_resize(&mut self, reset: bool) {
+        self.record(Event::ResetOnResize(reset));
+        self.reset_on_resize = reset;
+    }
+
+    /// Keyframed splash patterns or ballistic spray droplets
+    pub fn set_splash_mode(&mut self, mode: SplashMode) {
+        self.record(Event::SplashMode(mode));
+        self.entities.splashes.mode = mode;
+    }
+
+    /// Replace the splash sprites with a sheet in the binary format
+    pub fn load_splash_sheet(&mut self, bytes: &[u8]) -> Result<(), &'static str> {
+        self.encoder.set_sheet(SpriteSheet::from_bytes(bytes)?);
+        self.record(Event::SplashSheet(bytes));
+        Ok(())
+    }
+
+    /// Back to the built-in splash sprites
+    pub fn reset_splash_sheet(&mut self) {
+        self.record(Event::ResetSplashSheet);
+        self.encoder.set_sheet(SpriteSheet::builtin());
+    }
+
+    /// Resize the entity pools; live entities are kept up to the new size
+    pub fn set_capacity(&mut self, sizes: PoolSizes) {
+        let sizes = sizes.clamped();
+        self.record(Event::Capacity(sizes));
+        let e = &mut self.entities;
+        e.drops.set_capacity(sizes.drops);
+        e.splashes.set_capacity(sizes.splashes);
+        e.streams.set_capacity(sizes.streams);
+    }
+
+    pub fn capacity(&self) -> PoolSizes {
+        let e = &self.entities;
+        PoolSizes {
+            drops: e.drops.capacity(),
+            splashes: e.splashes.capacity(),
+            streams: e.streams.capacity(),
+        }
+    }
 
-        // Spawn new drops
-        let spawn_count = ((self.w >> 6) + 1) as usize;
-        self.drops.spawn(spawn_count, self.w as f32, &mut self.rng);
-
-        // Update entities
-        self.drops.update(
-            self.w as f32,
-            self.h as f32,
-            self.scale_x,
-            self.scale_y,
-            &mut self.splashes,
-            &mut self.streams,
-            &mut self.rng,
-        );
-
-        self.splashes.update();
-        self.streams.update(
-            self.w as f32,
-            self.h as f32,
-            self.scale_x,
-            self.scale_y,
-            &mut self.splashes,
-        );
-
-        // Render to output buffer
-        self.encoder.encode_drops(&self.drops, self.w as i32, self.h as i32);
-        self.encoder.encode_splashes(&self.splashes, self.w as i32, self.h as i32, &mut self.rng);
-        self.encoder.encode_streams(&self.streams, self.w as i32, self.h as i32);
+    /// Spawns refused by full pools: [drops, splashes, streams]
+    pub fn dropped_spawns(&self) -> [u32; 3] {
+        let e = &self.entities;
+        [e.drops.dropped, e.splashes.dropped, e.streams.dropped]
+    }
+
+    pub fn reset_dropped_spawns(&mut self) {
+        self.record(Event::ResetDroppedSpawns);
+        let e = &mut self.entities;
+        e.drops.dropped = 0;
+        e.splashes.dropped = 0;
+        e.streams.dropped = 0;
+    }
+
+    /// Set one tunable parameter by name (range-checked)
+    pub fn set_config_param(&mut self, name: &str, value: f32) -> Result<(), &'static str> {
+        self.config.set(name, value)?;
+        self.record(Event::ConfigParam(name, value));
+        Ok(())
+    }
+
+    /// Replace the whole config
+    pub fn set_config(&mut self, config: SimConfig) {
+        self.record(Event::Config(&config));
+        self.config = config;
+    }
+
+    /// Replace the config from JSON; on error the current one is kept
+    pub fn load_config_json(&mut self, text: &str) -> Result<(), &'static str> {
+        self.set_config(SimConfig::from_json(text)?);
+        Ok(())
+    }
+
+    pub fn apply_config_preset(&mut self, preset: ConfigPreset) {
+        self.record(Event::ConfigPreset(preset));
+        self.config = SimConfig::preset(preset);
+    }
+
+    /// Reseed every random stream. A world seeded before its first tick and
+    /// fed the same calls reproduces its output exactly (native or wasm).
+    pub fn set_seed(&mut self, seed: u32) {
+        self.record(Event::Seed(seed));
+        self.seed = seed;
+        self.systems.reseed(seed);
+        self.entities.impacts.seed(rng::stream(seed, rng::IMPACT));
+        self.weather.seed(rng::stream(seed, rng::WEATHER));
+        self.wind.seed(rng::stream(seed, rng::WIND));
+    }
+
+    /// Register a custom system at an order key (see the ORDER_* keys of
+    /// the built-ins); names must be unique
+    pub fn add_system(&mut self, order: i32, system: Box<dyn System>) -> Result<(), &'static str> {
+        self.systems.add(order, system, self.seed)
+    }
+
+    /// Unregister a system by name (built-ins included)
+    pub fn remove_system(&mut self, name: &str) -> bool {
+        self.systems.remove(name)
+    }
+
+    /// (name, order) of every system, in run order
+    pub fn systems(&self) -> impl Iterator<Item = (&'static str, i32)> + '_ {
+        self.systems.list()
+    }
+
+    /// Shared entity storage: built-in pools, and custom pools by type
+    pub fn entities(&self) -> &Entities { &self.entities }
+    pub fn entities_mut(&mut self) -> &mut Entities { &mut self.entities }
+
+    /// Switch between rain, snow and hail (entities already falling finish their fall)
+    pub fn set_precipitation(&mut self, kind: Precipitation) {
+        self.record(Event::Precipitation(kind));
+        self.precipitation = kind;
+    }
+
+    /// Start logging calls and frames for replay (see replay.rs); with
+    /// `checksums`, every frame's output is hashed so a replay can find
+    /// where it diverged. Restarts a recording already running.
+    pub fn start_recording(&mut self, checksums: bool) {
+        self.recorder = Some(Recorder::start(self, checksums));
+    }
+
+    /// Stop recording and take the log (None if not recording)
+    pub fn stop_recording(&mut self) -> Option<Vec<u8>> {
+        self.recorder.take().map(Recorder::finish)
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recorder.is_some()
+    }
+
+    fn record(&mut self, event: Event) {
+        if let Some(r) = &mut self.recorder {
+            r.event(&event);
+        }
     }
 
     // Random number generator (xorshift32)
@@ -112,6 +563,17 @@ impl RainWorld {
     // Accessors for WASM
     pub fn output_ptr(&self) -> *const u8 { self.encoder.ptr() }
     pub fn output_len(&self) -> usize { self.encoder.len() }
+    pub fn output(&self) -> &[u8] { self.encoder.as_slice() }
     pub fn width(&self) -> u32 { self.w }
     pub fn height(&self) -> u32 { self.h }
+    pub fn seed(&self) -> u32 { self.seed }
+    pub fn config(&self) -> &SimConfig { &self.config }
+    pub fn viewport(&self) -> &Viewport { &self.viewport }
+    pub fn wind(&self) -> f32 { self.wind.current() * TICK_RATE }
+    pub fn weather(&self) -> WeatherState { self.weather.state() }
+    pub fn weather_intensity(&self) -> f32 { self.weather.intensity() }
+    pub fn precipitation(&self) -> Precipitation { self.precipitation }
+    pub fn lightning_flash(&self) -> f32 { self.entities.lightning.flash() }
+    pub fn splash_mode(&self) -> SplashMode { self.entities.splashes.mode }
+    pub fn reset_on_resize(&self) -> bool { self.reset_on_resize }
 }
@@ -0,0 +1,183 @@
+// pool.rs - Structure-of-Arrays entity pools
+//
+// soa_pool! declares an entity's fields once and generates:
+//   - a plain record struct (one entity, Copy) for spawning and updating
+//   - the pool: one Vec per field plus count, capacity and a counter of
+//     spawns refused because the pool was full, and this frame's spawns,
+//     removals and refusals for the stats (reset_counts() starts a frame)
+//   - new / clear / capacity / set_capacity / push / get / retain / iter
+//   - save / load: capacity, counters and the live entities for snapshots
+//
+// retain() is the read/write compaction loop every update used to repeat:
+// it hands each live entity to a closure, which edits it in place and
+// returns false to remove it. Survivors are packed to the front in order.
+//
+// Extra (non-per-entity) fields go in an optional `extra` block with their
+// initial values.
+//
+//     soa_pool! {
+//         pub struct Ripples / Ripple {
+//             x: f32,
+//             r: f32,
+//             age: u8,
+//         }
+//     }
+
+macro_rules! soa_pool {
+    (
+        $(#[$meta:meta])*
+        pub struct $pool:ident / $record:ident {
+            $( $field:ident : $ty:ty ),* $(,)?
+        }
+        $( extra {
+            $( $evis:vis $extra:ident : $ety:ty = $einit:expr ),* $(,)?
+        } )?
+    ) => {
+        /// One entity (see the pool of the same kind)
+        #[derive(Clone, Copy, Debug, Default, PartialEq)]
+        pub struct $record {
+            $( pub $field: $ty, )*
+        }
+
+        $(#[$meta])*
+        pub struct $pool {
+            $( pub $field: Vec<$ty>, )*
+
+            // Count
+            pub n: usize,
+
+            // Spawns refused because the pool was full
+            pub dropped: u32,
+
+            // This frame: entities added, removed by retain, refused (full)
+            pub spawned: u32,
+            pub died: u32,
+            pub refused: u32,
+
+            cap: usize,
+
+            $( $( $evis $extra: $ety, )* )?
+        }
+
+        #[allow(dead_code)]
+        impl $pool {
+            pub fn new(capacity: usize) -> Self {
+                Self {
+                    $( $field: vec![<$ty>::default(); capacity], )*
+                    n: 0,
+                    dropped: 0,
+                    spawned: 0,
+                    died: 0,
+                    refused: 0,
+                    cap: capacity,
+                    $( $( $extra: $einit, )* )?
+                }
+            }
+
+            pub fn clear(&mut self) {
+                self.n = 0;
+            }
+
+            /// Zero this frame's spawn / removal / refusal counts
+            pub fn reset_counts(&mut self) {
+                self.spawned = 0;
+                self.died = 0;
+                self.refused = 0;
+            }
+
+            pub fn capacity(&self) -> usize {
+                self.cap
+            }
+
+            pub fn is_full(&self) -> bool {
+                self.n >= self.cap
+            }
+
+            /// Grow or shrink the pool (the newest entities go if it shrinks)
+            pub fn set_capacity(&mut self, capacity: usize) {
+                $(
+                    self.$field.resize(capacity, <$ty>::default());
+                    self.$field.shrink_to_fit();
+                )*
+                self.cap = capacity;
+                self.n = self.n.min(capacity);
+            }
+
+            /// Add an entity; false (and counted in `dropped`) if full
+            pub fn push(&mut self, e: $record) -> bool {
+                if self.is_full() {
+                    self.dropped = self.dropped.saturating_add(1);
+                    self.refused = self.refused.saturating_add(1);
+                    return false;
+                }
+                let i = self.n;
+                self.set(i, e);
+                self.n += 1;
+                self.spawned += 1;
+                true
+            }
+
+            #[inline(always)]
+            pub fn get(&self, i: usize) -> $record {
+                $record { $( $field: self.$field[i], )* }
+            }
+
+            #[inline(always)]
+            fn set(&mut self, i: usize, e: $record) {
+                $( self.$field[i] = e.$field; )*
+            }
+
+            /// Update every entity in place; remove those for which `f`
+            /// returns false (order is kept)
+            #[inline(always)]
+            pub fn retain(&mut self, mut f: impl FnMut(&mut $record) -> bool) {
+                let mut write = 0;
+                for read in 0..self.n {
+                    let mut e = self.get(read);
+                    if f(&mut e) {
+                        self.set(write, e);
+                        write += 1;
+                    }
+                }
+                self.died += (self.n - write) as u32;
+                self.n = write;
+            }
+
+            pub fn iter(&self) -> impl Iterator<Item = $record> + '_ {
+                (0..self.n).map(move |i| self.get(i))
+            }
+
+            /// Write capacity, counters and live entities (one column per
+            /// field); extra fields are left to the owner
+            pub fn save(&self, w: &mut $crate::sim::SnapWriter) {
+                w.u32(self.cap as u32);
+                w.u32(self.n as u32);
+                w.u32(self.dropped);
+                $(
+                    for &v in &self.$field[..self.n] {
+                        $crate::sim::SnapField::put(v, w);
+                    }
+                )*
+            }
+
+            /// Read back what save wrote (resizes the pool to match)
+            pub fn load(&mut self, r: &mut $crate::sim::SnapReader) -> Result<(), &'static str> {
+                let cap = r.u32()? as usize;
+                let n = r.u32()? as usize;
+                if cap > $crate::sim::MAX_POOL || n > cap { return Err("snapshot is corrupt"); }
+
+                self.set_capacity(cap);
+                self.dropped = r.u32()?;
+                $(
+                    for v in &mut self.$field[..n] {
+                        *v = $crate::sim::SnapField::take(r)?;
+                    }
+                )*
+                self.n = n;
+                Ok(())
+            }
+        }
+    };
+}
+
+pub(crate) use soa_pool;
@@ -0,0 +1,78 @@
+// puddle.rs - Standing water layer
+//
+// Per-pixel water level in background space. Streams that pool or run
+// out deposit water here; it slowly evaporates. Drops landing on a wet
+// pixel ripple like they would on a lake.
+
+use super::{ticks, SnapReader, SnapWriter};
+use crate::scene::{BG_WIDTH, BG_HEIGHT};
+
+const PUDDLE_DEPOSIT: u8 = 24;                    // water added per pooled stream
+const PUDDLE_WET: u8 = 64;                        // level at which a pixel counts as puddle
+const PUDDLE_DRY_TICKS: u16 = ticks(0.5) as u16;  // ticks between evaporation steps
+
+pub struct Puddles {
+    level: Vec<u8>,  // BG_WIDTH x BG_HEIGHT, row-major
+    dry_timer: u16,
+}
+
+impl Puddles {
+    pub fn new() -> Self {
+        Self {
+            level: vec![0; BG_WIDTH * BG_HEIGHT],
+            dry_timer: 0,
+        }
+    }
+
+    /// Add water around a background pixel (3x3, center weighted)
+    pub fn deposit(&mut self, bx: usize, by: usize) {
+        if bx >= BG_WIDTH || by >= BG_HEIGHT { return; }
+
+        for dy in -1i32..=1 {
+            for dx in -1i32..=1 {
+                let x = bx as i32 + dx;
+                let y = by as i32 + dy;
+                if (x as u32) >= BG_WIDTH as u32 || (y as u32) >= BG_HEIGHT as u32 { continue; }
+
+                let amount = if dx == 0 && dy == 0 { PUDDLE_DEPOSIT } else { PUDDLE_DEPOSIT / 2 };
+                let idx = y as usize * BG_WIDTH + x as usize;
+                self.level[idx] = self.level[idx].saturating_add(amount);
+            }
+        }
+    }
+
+    /// Is there standing water at this background pixel?
+    #[inline(always)]
+    pub fn is_wet(&self, bx: usize, by: usize) -> bool {
+        if bx >= BG_WIDTH || by >= BG_HEIGHT { return false; }
+        self.level[by * BG_WIDTH + bx] >= PUDDLE_WET
+    }
+
+    /// Evaporate a little every PUDDLE_DRY_TICKS
+    pub fn update(&mut self) {
+        self.dry_timer += 1;
+        if self.dry_timer < PUDDLE_DRY_TICKS { return; }
+        self.dry_timer = 0;
+
+        for v in self.level.iter_mut() {
+            *v = v.saturating_sub(1);
+        }
+    }
+
+    pub fn save(&self, w: &mut SnapWriter) {
+        w.rle(&self.level);
+        w.u16(self.dry_timer);
+    }
+
+    pub fn load(&mut self, r: &mut SnapReader) -> Result<(), &'static str> {
+        r.rle(&mut self.level)?;
+        self.dry_timer = r.u16()?;
+        Ok(())
+    }
+}
+
+impl Default for Puddles {
+    fn default() -> Self {
+        Self::new()
+    }
+}
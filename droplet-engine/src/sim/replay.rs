@@ -0,0 +1,313 @@
+// replay.rs - Input recording and deterministic replay
+//
+// A recording starts from a snapshot of the world and then logs every call
+// that changes it - seed, config, resizes, viewport, wind, weather, clear()
+// and the other settings - in order, interleaved with the frames: each
+// tick() or tick_dt() with its elapsed time and, optionally, a checksum of
+// the output buffer it rendered.
+//
+// Replaying restores the snapshot, re-issues the calls and compares each
+// frame's checksum. The simulation is deterministic (see rng.rs), so the
+// first frame that differs is where the two runs diverged - on another
+// platform, or after a change to the engine. Replay stops there, leaving the
+// world at that frame for inspection.
+//
+// Not recorded: custom systems being added or removed (register the same
+// ones on the replaying world first) and a splash sheet loaded before the
+// recording started.
+//
+// Format: "DRPR" version:u16 checksums:bool snapshot:bytes, then events,
+// each a tag byte and its payload (frames carry their checksum when on).
+
+use super::{ConfigPreset, PoolSizes, Precipitation, RainWorld, SimConfig, SnapReader, SnapWriter, SplashMode, ViewportMode, WeatherState};
+use super::snapshot::screen_size_ok;
+
+const MAGIC: &[u8; 4] = b"DRPR";
+const VERSION: u16 = 1;
+
+const CORRUPT: &str = "recording is corrupt";
+
+// Event tags
+const TICK: u8 = 0;
+const TICK_DT: u8 = 1;
+const RESIZE: u8 = 2;
+const VIEWPORT: u8 = 3;
+const CELL_ASPECT: u8 = 4;
+const RESET_ON_RESIZE: u8 = 5;
+const WIND: u8 = 6;
+const WIND_DEPTH_DRIFT: u8 = 7;
+const WEATHER: u8 = 8;
+const WEATHER_AUTO: u8 = 9;
+const PRECIPITATION: u8 = 10;
+const SPLASH_MODE: u8 = 11;
+const SEED: u8 = 12;
+const CONFIG_PARAM: u8 = 13;
+const CONFIG: u8 = 14;
+const CONFIG_PRESET: u8 = 15;
+const CAPACITY: u8 = 16;
+const RESET_DROPPED: u8 = 17;
+const SPLASH_SHEET: u8 = 18;
+const RESET_SPLASH_SHEET: u8 = 19;
+const RESTORE: u8 = 20;
+const CLEAR: u8 = 21;
+const IMPACT_EVENTS: u8 = 22;
+
+/// A recorded call (borrowing any bulk data)
+pub(crate) enum Event<'a> {
+    Resize(u32, u32),
+    Viewport(ViewportMode, f32, f32),
+    CellAspect(f32),
+    ResetOnResize(bool),
+    Wind(f32),
+    WindDepthDrift(f32),
+    Weather(WeatherState, f32),
+    WeatherAuto(bool),
+    Precipitation(Precipitation),
+    SplashMode(SplashMode),
+    Seed(u32),
+    ConfigParam(&'a str, f32),
+    Config(&'a SimConfig),
+    ConfigPreset(ConfigPreset),
+    Capacity(PoolSizes),
+    ResetDroppedSpawns,
+    SplashSheet(&'a [u8]),
+    ResetSplashSheet,
+    Restore(&'a [u8]),
+    Clear,
+    ImpactEvents(u32, f32),
+}
+
+/// Log being written while a world records
+pub(crate) struct Recorder {
+    w: SnapWriter,
+    checksums: bool,
+}
+
+impl Recorder {
+    pub fn start(world: &RainWorld, checksums: bool) -> Self {
+        let mut w = SnapWriter::new();
+        for &b in MAGIC {
+            w.u8(b);
+        }
+        w.u16(VERSION);
+        w.bool(checksums);
+        w.bytes(&world.snapshot());
+        Self { w, checksums }
+    }
+
+    pub fn finish(self) -> Vec<u8> {
+        self.w.into_bytes()
+    }
+
+    /// One tick (`dt` = None) or tick_dt, after it rendered `output`
+    pub fn frame(&mut self, dt: Option<f32>, output: &[u8]) {
+        match dt {
+            None => self.w.u8(TICK),
+            Some(dt) => {
+                self.w.u8(TICK_DT);
+                self.w.f32(dt);
+            }
+        }
+        if self.checksums {
+            self.w.u32(checksum(output));
+        }
+    }
+
+    pub fn event(&mut self, event: &Event) {
+        let w = &mut self.w;
+        match *event {
+            Event::Resize(width, height) => {
+                w.u8(RESIZE);
+                w.u32(width);
+                w.u32(height);
+            }
+            Event::Viewport(mode, ax, ay) => {
+                w.u8(VIEWPORT);
+                w.u8(mode as u8);
+                w.f32(ax);
+                w.f32(ay);
+            }
+            Event::CellAspect(aspect) => {
+                w.u8(CELL_ASPECT);
+                w.f32(aspect);
+            }
+            Event::ResetOnResize(reset) => {
+                w.u8(RESET_ON_RESIZE);
+                w.bool(reset);
+            }
+            Event::Wind(strength) => {
+                w.u8(WIND);
+                w.f32(strength);
+            }
+            Event::WindDepthDrift(drift) => {
+                w.u8(WIND_DEPTH_DRIFT);
+                w.f32(drift);
+            }
+            Event::Weather(state, seconds) => {
+                w.u8(WEATHER);
+                w.u8(state as u8);
+                w.f32(seconds);
+            }
+            Event::WeatherAuto(auto) => {
+                w.u8(WEATHER_AUTO);
+                w.bool(auto);
+            }
+            Event::Precipitation(kind) => {
+                w.u8(PRECIPITATION);
+                w.u8(kind as u8);
+            }
+            Event::SplashMode(mode) => {
+                w.u8(SPLASH_MODE);
+                w.u8(mode as u8);
+            }
+            Event::Seed(seed) => {
+                w.u8(SEED);
+                w.u32(seed);
+            }
+            Event::ConfigParam(name, value) => {
+                w.u8(CONFIG_PARAM);
+                w.str(name);
+                w.f32(value);
+            }
+            Event::Config(config) => {
+                w.u8(CONFIG);
+                for name in SimConfig::names() {
+                    w.f32(config.get(name).unwrap_or(0.0));
+                }
+            }
+            Event::ConfigPreset(preset) => {
+                w.u8(CONFIG_PRESET);
+                w.u8(preset as u8);
+            }
+            Event::Capacity(sizes) => {
+                w.u8(CAPACITY);
+                w.u32(sizes.drops as u32);
+                w.u32(sizes.splashes as u32);
+                w.u32(sizes.streams as u32);
+            }
+            Event::ResetDroppedSpawns => w.u8(RESET_DROPPED),
+            Event::SplashSheet(bytes) => {
+                w.u8(SPLASH_SHEET);
+                w.bytes(bytes);
+            }
+            Event::ResetSplashSheet => w.u8(RESET_SPLASH_SHEET),
+            Event::Restore(bytes) => {
+                w.u8(RESTORE);
+                w.bytes(bytes);
+            }
+            Event::Clear => w.u8(CLEAR),
+            Event::ImpactEvents(cap, rate) => {
+                w.u8(IMPACT_EVENTS);
+                w.u32(cap);
+                w.f32(rate);
+            }
+        }
+    }
+}
+
+/// Outcome of a replay
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ReplayReport {
+    pub frames: u32,                    // frames re-run (up to and including a divergent one)
+    pub checksums: bool,                // the recording has per-frame checksums
+    pub first_divergence: Option<u32>,  // first frame whose output differed
+}
+
+/// FNV-1a of an output buffer
+pub fn checksum(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0x811C_9DC5, |h, &b| (h ^ b as u32).wrapping_mul(0x0100_0193))
+}
+
+/// Re-run a recording on `world`, replacing its state. Errors on a bad
+/// recording; a corrupt event partway through leaves the world wherever
+/// the replay had got to.
+pub fn replay(world: &mut RainWorld, bytes: &[u8]) -> Result<ReplayReport, &'static str> {
+    let mut r = SnapReader::new(bytes);
+    for &b in MAGIC {
+        if r.u8().ok() != Some(b) { return Err("not a droplet recording"); }
+    }
+    if r.u16()? != VERSION { return Err("unsupported recording version"); }
+    let checksums = r.bool()?;
+    world.restore(r.bytes()?)?;
+
+    let mut report = ReplayReport { frames: 0, checksums, first_divergence: None };
+    while !r.is_empty() {
+        let tag = r.u8()?;
+        match tag {
+            TICK | TICK_DT => {
+                if tag == TICK {
+                    world.tick();
+                } else {
+                    world.tick_dt(r.f32()?);
+                }
+                report.frames += 1;
+
+                if checksums && r.u32()? != checksum(world.output()) {
+                    report.first_divergence = Some(report.frames - 1);
+                    break;
+                }
+            }
+            _ => apply(world, tag, &mut r)?,
+        }
+    }
+    Ok(report)
+}
+
+// Re-issue one recorded call
+fn apply(world: &mut RainWorld, tag: u8, r: &mut SnapReader) -> Result<(), &'static str> {
+    match tag {
+        RESIZE => {
+            let (w, h) = (r.u32()?, r.u32()?);
+            if !screen_size_ok(w, h) { return Err(CORRUPT); }
+            world.resize(w, h);
+        }
+        VIEWPORT => {
+            let mode = ViewportMode::from_u8(r.u8()?).ok_or(CORRUPT)?;
+            let (ax, ay) = (r.f32()?, r.f32()?);
+            world.set_viewport(mode, ax, ay);
+        }
+        CELL_ASPECT => world.set_cell_aspect(r.f32()?),
+        RESET_ON_RESIZE => world.set_reset_on_resize(r.bool()?),
+        WIND => world.set_wind(r.f32()?),
+        WIND_DEPTH_DRIFT => world.set_wind_depth_drift(r.f32()?),
+        WEATHER => {
+            let state = WeatherState::from_u8(r.u8()?).ok_or(CORRUPT)?;
+            world.set_weather(state, r.f32()?);
+        }
+        WEATHER_AUTO => world.set_weather_auto(r.bool()?),
+        PRECIPITATION => world.set_precipitation(Precipitation::from_u8(r.u8()?).ok_or(CORRUPT)?),
+        SPLASH_MODE => world.set_splash_mode(SplashMode::from_u8(r.u8()?).ok_or(CORRUPT)?),
+        SEED => world.set_seed(r.u32()?),
+        CONFIG_PARAM => {
+            let name = r.str()?;
+            world.set_config_param(name, r.f32()?).map_err(|_| CORRUPT)?;
+        }
+        CONFIG => {
+            let mut config = SimConfig::DEFAULT;
+            for name in SimConfig::names() {
+                config.set(name, r.f32()?).map_err(|_| CORRUPT)?;
+            }
+            world.set_config(config);
+        }
+        CONFIG_PRESET => world.apply_config_preset(ConfigPreset::from_u8(r.u8()?).ok_or(CORRUPT)?),
+        CAPACITY => {
+            let (drops, splashes, streams) = (r.u32()?, r.u32()?, r.u32()?);
+            world.set_capacity(PoolSizes {
+                drops: drops as usize,
+                splashes: splashes as usize,
+                streams: streams as usize,
+            });
+        }
+        RESET_DROPPED => world.reset_dropped_spawns(),
+        SPLASH_SHEET => world.load_splash_sheet(r.bytes()?).map_err(|_| CORRUPT)?,
+        RESET_SPLASH_SHEET => world.reset_splash_sheet(),
+        RESTORE => world.restore(r.bytes()?).map_err(|_| CORRUPT)?,
+        CLEAR => world.clear(),
+        IMPACT_EVENTS => {
+            let cap = r.u32()?;
+            world.set_impact_events(cap as usize, r.f32()?);
+        }
+        _ => return Err(CORRUPT),
+    }
+    Ok(())
+}
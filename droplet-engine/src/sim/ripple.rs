@@ -0,0 +1,46 @@
+// ripple.rs - Expanding rings on water surfaces
+//
+// Spawned instead of splashes when drops land on water or puddles.
+
+use super::{soa_pool, STEP, ticks};
+
+const RIPPLE_FRAMES: u8 = ticks(0.667) as u8;
+const RIPPLE_GROWTH: f32 = 9.0 * STEP;         // radius growth (near, cells/s)
+
+soa_pool! {
+    pub struct Ripples / Ripple {
+        // Position (ring center)
+        x: f32,
+        y: f32,
+        z: f32,
+
+        // Ring
+        r: f32,     // horizontal radius (screen cells)
+        age: u8,
+    }
+}
+
+impl Ripples {
+    /// Spawn a new ripple
+    pub fn spawn(&mut self, x: f32, y: f32, z: f32) {
+        self.push(Ripple { x, y, z, r: 0.0, age: 0 });
+    }
+
+    /// Grow rings, remove faded ones
+    pub fn update(&mut self) {
+        self.retain(|e| {
+            e.age += 1;
+            if e.age >= RIPPLE_FRAMES { return false; }
+
+            // Far rings grow slower on screen (perspective)
+            e.r += RIPPLE_GROWTH * (1.0 - e.z * 0.6);
+            true
+        });
+    }
+
+    /// Fade level 0 (fresh) to 3 (almost gone)
+    #[inline]
+    pub fn fade(age: u8) -> u8 {
+        (age as u32 * 4 / RIPPLE_FRAMES as u32) as u8
+    }
+}
@@ -0,0 +1,45 @@
+// rng.rs - Seeded random streams
+//
+// Every subsystem draws from its own xorshift32 state, split off the world
+// seed by stream id. Streams never share state, so more drops landing
+// doesn't shift when the weather turns or where lightning strikes.
+//
+// All randomness is integer arithmetic and the simulation only uses exactly
+// rounded float ops (see math.rs for sin/cos), so a seed plus the same
+// sequence of calls gives bit-identical output on native and wasm32.
+
+pub const DEFAULT_SEED: u32 = 0xDEAD_BEEF;
+
+// Stream ids
+pub const SPAWN: u32 = 1;      // new drops, flakes, hail, drip release
+pub const DROPLET: u32 = 2;    // drop impacts (slide / splash chance, splash placement, spray launch)
+pub const WEATHER: u32 = 3;    // auto weather
+pub const WIND: u32 = 4;       // gust targets
+pub const IMPACT: u32 = 5;     // impact event sampling
+
+// Custom systems get a stream keyed by their name, high bit set so it never
+// lands on a built-in id
+const NAMED: u32 = 0x8000_0000;
+
+/// Initial state of stream `id` for `seed` (murmur3 finalizer, never 0)
+pub const fn stream(seed: u32, id: u32) -> u32 {
+    let mut z = seed ^ id.wrapping_mul(0x9E37_79B9);
+    z = (z ^ (z >> 16)).wrapping_mul(0x85EB_CA6B);
+    z = (z ^ (z >> 13)).wrapping_mul(0xC2B2_AE35);
+    z ^= z >> 16;
+
+    // xorshift never leaves 0
+    if z == 0 { 0x9E37_79B9 } else { z }
+}
+
+/// Stream id for a system name (FNV-1a)
+pub const fn named(name: &str) -> u32 {
+    let bytes = name.as_bytes();
+    let mut h: u32 = 0x811C_9DC5;
+    let mut i = 0;
+    while i < bytes.len() {
+        h = (h ^ bytes[i] as u32).wrapping_mul(0x0100_0193);
+        i += 1;
+    }
+    h | NAMED
+}
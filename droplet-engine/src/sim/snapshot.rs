@@ -0,0 +1,327 @@
+// snapshot.rs - Save and restore the whole simulation
+//
+// A snapshot is a compact little-endian byte blob:
+//
+//   "DRPS" version:u16 scene:(BG_WIDTH, BG_HEIGHT):u16x2
+//   screen, seed, config, precipitation, viewport, fixed-step timing
+//   wind, weather
+//   entity pools (live entities only, one column per field)
+//   lightning, puddles, drips, snow (byte layers run-length encoded)
+//   impact event settings (the queue itself is per frame and not saved)
+//   systems: name, RNG state, and a length-prefixed section each
+//
+// Restoring continues bit-identically to the world that was saved, at the
+// saved screen size and pool capacities (resize afterwards to fit a new
+// canvas; entities are rescaled as usual). Systems missing from the world
+// restoring it are skipped, and systems the snapshot doesn't know keep
+// their state. Custom systems persist their own pools through System::save
+// / System::load. The splash sprite sheet and the output buffer are not
+// state: the restored world re-renders at once.
+//
+// Bump VERSION whenever the layout changes; older blobs are rejected.
+
+use std::ops::RangeInclusive;
+
+use super::replay::Event;
+use super::{RainWorld, Entities, Precipitation, STEP, SimConfig, SplashMode, Viewport, ViewportMode, Weather, Wind};
+use crate::scene::{BG_WIDTH, BG_HEIGHT};
+
+const MAGIC: &[u8; 4] = b"DRPS";
+const VERSION: u16 = 5;
+
+// Largest screen a snapshot may ask for, in cells (far past any display)
+const MAX_SCREEN_CELLS: u32 = 1 << 24;
+
+/// Whether a stored screen size is one we can allocate (shared with replay)
+pub(super) fn screen_size_ok(w: u32, h: u32) -> bool {
+    w > 0 && h > 0 && w.checked_mul(h).is_some_and(|n| n <= MAX_SCREEN_CELLS)
+}
+
+const TRUNCATED: &str = "snapshot is truncated";
+const CORRUPT: &str = "snapshot is corrupt";
+
+/// Append-only snapshot encoder
+#[derive(Default)]
+pub struct SnapWriter {
+    buf: Vec<u8>,
+}
+
+impl SnapWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.buf
+    }
+
+    pub fn u8(&mut self, v: u8) { self.buf.push(v); }
+    pub fn u16(&mut self, v: u16) { self.buf.extend_from_slice(&v.to_le_bytes()); }
+    pub fn u32(&mut self, v: u32) { self.buf.extend_from_slice(&v.to_le_bytes()); }
+    pub fn f32(&mut self, v: f32) { self.u32(v.to_bits()); }
+    pub fn bool(&mut self, v: bool) { self.u8(v as u8); }
+
+    /// Length-prefixed bytes
+    pub fn bytes(&mut self, v: &[u8]) {
+        self.u32(v.len() as u32);
+        self.buf.extend_from_slice(v);
+    }
+
+    pub fn str(&mut self, v: &str) {
+        self.bytes(v.as_bytes());
+    }
+
+    /// Byte layer as (run length, value) pairs - mostly-empty grids shrink
+    /// to a few bytes
+    pub fn rle(&mut self, v: &[u8]) {
+        let mut i = 0;
+        while i < v.len() {
+            let value = v[i];
+            let mut run = 1;
+            while i + run < v.len() && v[i + run] == value && run < u16::MAX as usize {
+                run += 1;
+            }
+            self.u16(run as u16);
+            self.u8(value);
+            i += run;
+        }
+    }
+}
+
+/// Snapshot decoder; every read fails cleanly on short or bad data
+pub struct SnapReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> SnapReader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pos == self.bytes.len()
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], &'static str> {
+        let end = self.pos.checked_add(n).filter(|&e| e <= self.bytes.len()).ok_or(TRUNCATED)?;
+        let s = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(s)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], &'static str> {
+        let mut a = [0; N];
+        a.copy_from_slice(self.take(N)?);
+        Ok(a)
+    }
+
+    pub fn u8(&mut self) -> Result<u8, &'static str> { Ok(self.take(1)?[0]) }
+    pub fn u16(&mut self) -> Result<u16, &'static str> { Ok(u16::from_le_bytes(self.array()?)) }
+    pub fn u32(&mut self) -> Result<u32, &'static str> { Ok(u32::from_le_bytes(self.array()?)) }
+    pub fn f32(&mut self) -> Result<f32, &'static str> { Ok(f32::from_bits(self.u32()?)) }
+
+    /// f32 within `range` (NaN never is)
+    pub fn f32_in(&mut self, range: RangeInclusive<f32>) -> Result<f32, &'static str> {
+        Some(self.f32()?).filter(|v| range.contains(v)).ok_or(CORRUPT)
+    }
+
+    pub fn bool(&mut self) -> Result<bool, &'static str> {
+        match self.u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(CORRUPT),
+        }
+    }
+
+    /// xorshift state (never 0)
+    pub fn rng(&mut self) -> Result<u32, &'static str> {
+        match self.u32()? {
+            0 => Err(CORRUPT),
+            v => Ok(v),
+        }
+    }
+
+    pub fn bytes(&mut self) -> Result<&'a [u8], &'static str> {
+        let n = self.u32()? as usize;
+        self.take(n)
+    }
+
+    pub fn str(&mut self) -> Result<&'a str, &'static str> {
+        std::str::from_utf8(self.bytes()?).map_err(|_| CORRUPT)
+    }
+
+    /// Fill a byte layer written by SnapWriter::rle
+    pub fn rle(&mut self, out: &mut [u8]) -> Result<(), &'static str> {
+        let mut i = 0;
+        while i < out.len() {
+            let run = self.u16()? as usize;
+            let value = self.u8()?;
+            if run == 0 || i + run > out.len() { return Err(CORRUPT); }
+            out[i..i + run].fill(value);
+            i += run;
+        }
+        Ok(())
+    }
+}
+
+/// A pool field type that can go in a snapshot
+pub trait SnapField: Sized {
+    fn put(self, w: &mut SnapWriter);
+    fn take(r: &mut SnapReader) -> Result<Self, &'static str>;
+}
+
+impl SnapField for f32 {
+    fn put(self, w: &mut SnapWriter) { w.f32(self) }
+    fn take(r: &mut SnapReader) -> Result<Self, &'static str> { r.f32() }
+}
+
+impl SnapField for u8 {
+    fn put(self, w: &mut SnapWriter) { w.u8(self) }
+    fn take(r: &mut SnapReader) -> Result<Self, &'static str> { r.u8() }
+}
+
+impl SnapField for i8 {
+    fn put(self, w: &mut SnapWriter) { w.u8(self as u8) }
+    fn take(r: &mut SnapReader) -> Result<Self, &'static str> { Ok(r.u8()? as i8) }
+}
+
+impl SnapField for u16 {
+    fn put(self, w: &mut SnapWriter) { w.u16(self) }
+    fn take(r: &mut SnapReader) -> Result<Self, &'static str> { r.u16() }
+}
+
+impl SnapField for u32 {
+    fn put(self, w: &mut SnapWriter) { w.u32(self) }
+    fn take(r: &mut SnapReader) -> Result<Self, &'static str> { r.u32() }
+}
+
+impl RainWorld {
+    /// Serialize the whole simulation state
+    pub fn snapshot(&self) -> Vec<u8> {
+        let mut w = SnapWriter::new();
+        w.buf.extend_from_slice(MAGIC);
+        w.u16(VERSION);
+        w.u16(BG_WIDTH as u16);
+        w.u16(BG_HEIGHT as u16);
+
+        w.u32(self.w);
+        w.u32(self.h);
+        w.u32(self.seed);
+        for name in SimConfig::names() {
+            w.f32(self.config.get(name).unwrap_or(0.0));
+        }
+        w.u8(self.precipitation as u8);
+        w.bool(self.reset_on_resize);
+        let (ax, ay) = self.viewport.anchor();
+        w.u8(self.viewport.mode() as u8);
+        w.f32(ax);
+        w.f32(ay);
+        w.f32(self.viewport.cell_aspect());
+        w.f32(self.accumulator);
+        w.f32(self.alpha);
+
+        self.wind.save(&mut w);
+        self.weather.save(&mut w);
+
+        let e = &self.entities;
+        e.drops.save(&mut w);
+        e.splashes.save(&mut w);
+        w.f32(e.splashes.wind);
+        w.u8(e.splashes.frames);
+        w.u8(e.splashes.mode as u8);
+        e.splashes.spray.save(&mut w);
+        e.streams.save(&mut w);
+        e.ripples.save(&mut w);
+        e.flakes.save(&mut w);
+        e.hail.save(&mut w);
+        e.lightning.save(&mut w);
+        e.puddles.save(&mut w);
+        e.drips.save(&mut w);
+        e.snow.save(&mut w);
+        e.impacts.save(&mut w);
+
+        self.systems.save(e, &mut w);
+        w.into_bytes()
+    }
+
+    /// Replace the simulation state with a snapshot. On error the world is
+    /// left as it was (custom system sections are read last, and a failing
+    /// one can leave that system part-restored).
+    pub fn restore(&mut self, bytes: &[u8]) -> Result<(), &'static str> {
+        let mut r = SnapReader::new(bytes);
+        if r.take(4).ok() != Some(&MAGIC[..]) { return Err("not a droplet snapshot"); }
+        if r.u16()? != VERSION { return Err("unsupported snapshot version"); }
+        if r.u16()? as usize != BG_WIDTH || r.u16()? as usize != BG_HEIGHT {
+            return Err("snapshot is from a different scene");
+        }
+
+        let w = r.u32()?;
+        let h = r.u32()?;
+        if !screen_size_ok(w, h) { return Err(CORRUPT); }
+        let seed = r.u32()?;
+        let mut config = SimConfig::DEFAULT;
+        for name in SimConfig::names() {
+            config.set(name, r.f32()?).map_err(|_| CORRUPT)?;
+        }
+        let precipitation = Precipitation::from_u8(r.u8()?).ok_or(CORRUPT)?;
+        let reset_on_resize = r.bool()?;
+        let mode = ViewportMode::from_u8(r.u8()?).ok_or(CORRUPT)?;
+        let (ax, ay) = (r.f32_in(0.0..=1.0)?, r.f32_in(0.0..=1.0)?);
+        let aspect = r.f32_in(f32::MIN_POSITIVE..=f32::MAX)?;
+        let accumulator = r.f32_in(0.0..=STEP)?;
+        let alpha = r.f32_in(0.0..=1.0)?;
+
+        let mut wind = Wind::new();
+        wind.load(&mut r)?;
+        let mut weather = Weather::new();
+        weather.load(&mut r)?;
+
+        // Built-in pools into a fresh store; custom pools carry over
+        let mut e = Entities::new(0, 0, 0, 0, 0, 0);
+        e.drops.load(&mut r)?;
+        e.splashes.load(&mut r)?;
+        e.splashes.wind = r.f32()?;
+        e.splashes.frames = r.u8()?;
+        e.splashes.mode = SplashMode::from_u8(r.u8()?).ok_or(CORRUPT)?;
+        e.splashes.spray.load(&mut r)?;
+        e.streams.load(&mut r)?;
+        e.ripples.load(&mut r)?;
+        e.flakes.load(&mut r)?;
+        e.hail.load(&mut r)?;
+        e.lightning.load(&mut r)?;
+        e.puddles.load(&mut r)?;
+        e.drips.load(&mut r)?;
+        e.snow.load(&mut r)?;
+        e.impacts.load(&mut r)?;
+
+        // Validate the system sections before anything changes
+        let systems_at = r.pos;
+        self.systems.check(&mut r)?;
+        if !r.is_empty() { return Err(CORRUPT); }
+
+        self.w = w;
+        self.h = h;
+        self.seed = seed;
+        self.config = config;
+        self.precipitation = precipitation;
+        self.reset_on_resize = reset_on_resize;
+        self.viewport = Viewport::new(w, h);
+        self.viewport.set_cell_aspect(aspect, w, h);
+        self.viewport.set_mode(mode, ax, ay, w, h);
+        self.accumulator = accumulator;
+        self.alpha = alpha;
+        self.wind = wind;
+        self.weather = weather;
+        e.take_custom(&mut self.entities);
+        self.entities = e;
+        self.encoder.resize(w, h);
+
+        let mut r = SnapReader::new(&bytes[systems_at..]);
+        self.systems.load(&mut self.entities, &mut r)?;
+
+        self.render();
+        self.record(Event::Restore(bytes));
+        Ok(())
+    }
+}
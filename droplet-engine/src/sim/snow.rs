@@ -0,0 +1,90 @@
+// snow.rs - Settled snow layer
+//
+// Per-pixel snow depth in background space. Landed flakes build it up on
+// ground surfaces; steep surfaces shed it. Melts slowly when it isn't
+// snowing.
+
+use super::{ticks, SnapReader, SnapWriter};
+use crate::scene::{BG_WIDTH, BG_HEIGHT};
+use crate::world::is_ground;
+
+const SNOW_PER_FLAKE: f32 = 6.0;              // depth added by one flake on flat ground
+const SHED_SLOPE: f32 = 0.5;                  // sideways normal tilt at which nothing sticks
+const MELT_TICKS: u16 = ticks(0.333) as u16;  // ticks between melt steps
+
+pub struct Snow {
+    depth: Vec<u8>,  // BG_WIDTH x BG_HEIGHT, row-major (0 = bare)
+    melt_timer: u16,
+}
+
+impl Snow {
+    pub fn new() -> Self {
+        Self {
+            depth: vec![0; BG_WIDTH * BG_HEIGHT],
+            melt_timer: 0,
+        }
+    }
+
+    /// Land a flake at a background pixel with surface normal (nx, ny)
+    pub fn settle(&mut self, bx: usize, by: usize, nx: f32, ny: f32) {
+        if !is_ground(bx, by) { return; }
+
+        // Upward-facing surfaces (ny < 0) hold the most; sideways or
+        // downward-facing ones shed
+        let slope = nx.abs() + ny.max(0.0);
+        if slope >= SHED_SLOPE { return; }
+        let amount = (SNOW_PER_FLAKE * (1.0 - slope / SHED_SLOPE)) as u8;
+        if amount == 0 { return; }
+
+        // Spread over the neighbours so the screen grid always samples it
+        for dy in -1i32..=1 {
+            for dx in -1i32..=1 {
+                let x = bx as i32 + dx;
+                let y = by as i32 + dy;
+                if (x as u32) >= BG_WIDTH as u32 || (y as u32) >= BG_HEIGHT as u32 { continue; }
+                if !is_ground(x as usize, y as usize) { continue; }
+
+                let a = if dx == 0 && dy == 0 { amount } else { amount / 2 };
+                let idx = y as usize * BG_WIDTH + x as usize;
+                self.depth[idx] = self.depth[idx].saturating_add(a);
+            }
+        }
+    }
+
+    /// Snow depth at a background pixel (0-255)
+    #[inline(always)]
+    pub fn depth(&self, bx: usize, by: usize) -> u8 {
+        if bx >= BG_WIDTH || by >= BG_HEIGHT { return 0; }
+        self.depth[by * BG_WIDTH + bx]
+    }
+
+    /// Melt a little every MELT_TICKS while it isn't snowing
+    pub fn update(&mut self, snowing: bool) {
+        if snowing { return; }
+
+        self.melt_timer += 1;
+        if self.melt_timer < MELT_TICKS { return; }
+        self.melt_timer = 0;
+
+        for v in self.depth.iter_mut() {
+            *v = v.saturating_sub(1);
+        }
+    }
+
+    pub fn save(&self, w: &mut SnapWriter) {
+        w.rle(&self.depth);
+        w.u16(self.melt_timer);
+    }
+
+    pub fn load(&mut self, r: &mut SnapReader) -> Result<(), &'static str> {
+        r.rle(&mut self.depth)?;
+        self.melt_timer = r.u16()?;
+        Ok(())
+    }
+}
+
+impl Default for Snow {
+    fn default() -> Self {
+        Self::new()
+    }
+}
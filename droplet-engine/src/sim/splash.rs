@@ -1,111 +1,158 @@
 // splash.rs - Water splash effects
 //
 // Animated splash particles that appear on impact.
-
-use super::{MAX_SPLASHES, RainWorld};
-
-const SPLASH_FRAMES: u8 = 24;
-
-pub struct Splashes {
-    // Position
-    pub x: [f32; MAX_SPLASHES],
-    pub y: [f32; MAX_SPLASHES],
-    pub z: [f32; MAX_SPLASHES],
-
-    // Animation
-    pub frame: [u8; MAX_SPLASHES],
-    pub dir: [i8; MAX_SPLASHES],   // horizontal drift
-    pub typ: [u8; MAX_SPLASHES],   // splash type (0-3)
-
-    // Count
-    pub n: usize,
+// In ballistic mode an impact throws spray droplets instead of playing a
+// keyframed pattern.
+
+use super::{soa_pool, MAX_SPRAY, RainWorld, SimConfig, Spray, StepContext};
+use crate::world::{get_normal_bilinear, SplashStyle};
+
+const WIND_BIAS: f32 = 2.0;  // drift per unit of wind
+const SPRAY_BIAS: f32 = 0.5; // sideways push for directional splash types
+
+// Splash types
+pub const SPLASH_CROWN: u8 = 0;
+pub const SPLASH_LEFT: u8 = 1;
+pub const SPLASH_RIGHT: u8 = 2;
+pub const SPLASH_SPRAY: u8 = 3;
+pub const SPLASH_PUFF: u8 = 4;
+
+/// How impacts are drawn
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(u8)]
+pub enum SplashMode {
+    Pattern = 0,    // keyframed character patterns
+    Ballistic = 1,  // spray droplets with gravity and collision
 }
 
-impl Splashes {
-    pub fn new() -> Self {
-        Self {
-            x: [0.0; MAX_SPLASHES],
-            y: [0.0; MAX_SPLASHES],
-            z: [0.0; MAX_SPLASHES],
-            frame: [0; MAX_SPLASHES],
-            dir: [0; MAX_SPLASHES],
-            typ: [0; MAX_SPLASHES],
-            n: 0,
+impl SplashMode {
+    pub fn from_u8(v: u8) -> Option<Self> {
+        match v {
+            0 => Some(Self::Pattern),
+            1 => Some(Self::Ballistic),
+            _ => None,
         }
     }
+}
+
+soa_pool! {
+    pub struct Splashes / Splash {
+        // Position
+        x: f32,
+        y: f32,
+        z: f32,
+
+        // Animation
+        frame: u8,
+        dir: i8,   // horizontal drift
+        typ: u8,   // splash type (0-4)
+    }
+    extra {
+        // Wind this tick - biases drift downwind at spawn
+        pub wind: f32 = 0.0,
 
-    pub fn clear(&mut self) {
-        self.n = 0;
+        // Animation length in ticks (from SimConfig)
+        pub frames: u8 = SimConfig::DEFAULT.splash_frames(),
+
+        // Ballistic mode
+        pub mode: SplashMode = SplashMode::Pattern,
+        pub spray: Spray = Spray::new(MAX_SPRAY),
     }
+}
 
+impl Splashes {
     /// Spawn a new splash (random direction)
     pub fn spawn(&mut self, x: f32, y: f32, z: f32, typ: u8, rng: &mut u32) {
-        if self.n >= MAX_SPLASHES { return; }
-
-        let i = self.n;
-        self.x[i] = x + (RainWorld::rand(rng) - 0.5) * 4.0;
-        self.y[i] = y;
-        self.z[i] = z;
-        self.frame[i] = 0;
-        self.dir[i] = (RainWorld::rand(rng) * 5.0) as i8 - 2;
-        self.typ[i] = typ;
-        self.n += 1;
+        if self.mode == SplashMode::Ballistic {
+            self.emit_spray(x, y, z, (0.0, -1.0), typ, rng);
+            return;
+        }
+
+        let wind_bias = (self.wind * WIND_BIAS) as i8;
+        self.push(Splash {
+            x: x + (RainWorld::rand(rng) - 0.5) * 4.0,
+            y,
+            z,
+            frame: 0,
+            dir: ((RainWorld::rand(rng) * 5.0) as i8 - 2 + wind_bias).clamp(-5, 5),
+            typ,
+        });
     }
 
     /// Spawn a splash with direction biased by surface normal
-    /// nx, ny: surface normal components (-1 to 1)
-    pub fn spawn_with_normal(&mut self, x: f32, y: f32, z: f32, nx: f32, _ny: f32, rng: &mut u32) {
-        if self.n >= MAX_SPLASHES { return; }
-
-        let i = self.n;
-        self.x[i] = x + (RainWorld::rand(rng) - 0.5) * 4.0;
-        self.y[i] = y;
-        self.z[i] = z;
-        self.frame[i] = 0;
+    /// (bx, by): impact in background space (normal is sampled bilinearly there)
+    pub fn spawn_with_normal(&mut self, x: f32, y: f32, z: f32, (bx, by): (f32, f32), style: SplashStyle, rng: &mut u32) {
+        if self.mode == SplashMode::Ballistic {
+            let (nx, ny) = get_normal_bilinear(bx, by);
+            let typ = Self::style_type(style, nx, rng);
+            self.emit_spray(x, y, z, (nx, ny), typ, rng);
+            return;
+        }
+        let (nx, _ny) = get_normal_bilinear(bx, by);
+        let x = x + (RainWorld::rand(rng) - 0.5) * 4.0;
 
         // Horizontal drift biased by normal x-component
         // Normal pointing right (nx > 0) -> drift right, and vice versa
         let normal_bias = nx * 6.0;
         let random_component = (RainWorld::rand(rng) - 0.5) * 2.0;
-        self.dir[i] = (normal_bias + random_component).clamp(-5.0, 5.0) as i8;
-
-        // Choose splash type based on normal direction
-        // Even slight tilts bias toward directional splashes
-        // Probability of directional splash scales with |nx|
-        let r = RainWorld::rand(rng);
-        let abs_nx = nx.abs();
-
-        // Base probability for directional splash: 0.3 at nx=0, up to 0.9 at |nx|=1
-        let dir_prob = 0.3 + abs_nx * 0.6;
-
-        self.typ[i] = if r < dir_prob {
-            // Directional splash - choose based on sign of nx
-            if nx < 0.0 { 1 } else { 2 }  // 1=left burst, 2=right burst
-        } else {
-            // Symmetric splash
-            if RainWorld::rand(rng) < 0.5 { 0 } else { 3 }  // 0=crown, 3=spray
+        let wind_bias = self.wind * WIND_BIAS;
+        let dir = (normal_bias + random_component + wind_bias).clamp(-5.0, 5.0) as i8;
+
+        let typ = Self::style_type(style, nx, rng);
+        self.push(Splash { x, y, z, frame: 0, dir, typ });
+    }
+
+    // Throw droplets for a splash of this type (more for near, bigger splashes)
+    fn emit_spray(&mut self, x: f32, y: f32, z: f32, (nx, ny): (f32, f32), typ: u8, rng: &mut u32) {
+        let (base, side) = match typ {
+            SPLASH_CROWN => (6.0, 0.0),
+            SPLASH_LEFT => (4.0, -SPRAY_BIAS),
+            SPLASH_RIGHT => (4.0, SPRAY_BIAS),
+            SPLASH_SPRAY => (5.0, 0.0),
+            _ => (1.0, 0.0),
         };
+        let count = (base * (1.0 - z * 0.5)).round().max(1.0) as usize;
+        let bias = side + self.wind * WIND_BIAS * 0.25;
+        self.spray.emit((x, y, z), (nx, ny), bias, count, rng);
+    }
 
-        self.n += 1;
+    /// Pick a splash type for a material style
+    pub fn style_type(style: SplashStyle, nx: f32, rng: &mut u32) -> u8 {
+        match style {
+            SplashStyle::Crown => SPLASH_CROWN,
+            SplashStyle::Spray => SPLASH_SPRAY,
+            SplashStyle::Muted => SPLASH_PUFF,
+            SplashStyle::Normal => {
+                // Even slight tilts bias toward directional splashes
+                // Probability of directional splash scales with |nx|
+                let r = RainWorld::rand(rng);
+                let abs_nx = nx.abs();
+
+                // Base probability for directional splash: 0.3 at nx=0, up to 0.9 at |nx|=1
+                let dir_prob = 0.3 + abs_nx * 0.6;
+
+                if r < dir_prob {
+                    // Directional splash - choose based on sign of nx
+                    if nx < 0.0 { SPLASH_LEFT } else { SPLASH_RIGHT }
+                } else {
+                    // Symmetric splash
+                    if RainWorld::rand(rng) < 0.5 { SPLASH_CROWN } else { SPLASH_SPRAY }
+                }
+            }
+        }
     }
 
     /// Advance animation, remove finished splashes
     pub fn update(&mut self) {
-        let mut write = 0;
-
-        for read in 0..self.n {
-            let frame = self.frame[read] + 1;
-            if frame >= SPLASH_FRAMES { continue; }
-
-            self.x[write] = self.x[read];
-            self.y[write] = self.y[read];
-            self.z[write] = self.z[read];
-            self.frame[write] = frame;
-            self.dir[write] = self.dir[read];
-            self.typ[write] = self.typ[read];
-            write += 1;
-        }
+        let frames = self.frames;
+        self.retain(|e| {
+            e.frame += 1;
+            e.frame < frames
+        });
+    }
 
-        self.n = write;
+    /// Move spray droplets
+    pub fn update_spray(ctx: &mut StepContext) {
+        Spray::update(ctx);
     }
 }
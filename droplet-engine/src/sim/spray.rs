@@ -0,0 +1,104 @@
+// spray.rs - Ballistic splash droplets
+//
+// Secondary droplets thrown off an impact when splashes run in ballistic
+// mode. They leave along the surface normal, fall under gravity, and can
+// land on other surfaces - where flowing ones feed into streams.
+
+use super::{soa_pool, Entities, RainWorld, StepContext, STEP, ticks};
+use crate::world::has_flow;
+
+const SPRAY_SPEED: f32 = 36.0 * STEP;        // launch speed (near, cells/s)
+const SPRAY_SPREAD: f32 = 0.7;               // sideways scatter relative to the normal
+const GRAVITY: f32 = 180.0 * STEP * STEP;    // cells/s^2
+const SPRAY_LIFE: u8 = ticks(0.667) as u8;   // before a droplet evaporates
+const LAUNCH_TICKS: u8 = ticks(0.05) as u8;  // ignore collisions while leaving the surface
+const SLIDE_CHANCE: f32 = 0.3;               // landed on flow: joins a stream (times config slide_chance)
+
+soa_pool! {
+    pub struct Spray / SprayDrop {
+        // Position
+        x: f32,
+        y: f32,
+        z: f32,
+
+        // Velocity
+        vx: f32,
+        vy: f32,
+
+        // Ticks since launch
+        age: u8,
+    }
+}
+
+impl Spray {
+    /// Throw `count` droplets from (x, y, z) off a surface with normal (nx, ny)
+    /// bias: extra horizontal push (splash direction + wind)
+    pub fn emit(&mut self, (x, y, z): (f32, f32, f32), (nx, ny): (f32, f32), bias: f32, count: usize, rng: &mut u32) {
+        // Pixels facing the camera carry no slope - throw straight up
+        let len = (nx * nx + ny * ny).sqrt();
+        let (nx, ny) = if len < 0.1 { (0.0, -1.0) } else { (nx / len, ny / len) };
+
+        let speed = SPRAY_SPEED * (1.0 - z * 0.6);
+
+        for _ in 0..count {
+            if self.is_full() { return; }
+
+            // Along the normal, scattered along the surface tangent (-ny, nx)
+            let out = 0.6 + RainWorld::rand(rng) * 0.4;
+            let side = (RainWorld::rand(rng) * 2.0 - 1.0) * SPRAY_SPREAD;
+
+            self.push(SprayDrop {
+                x,
+                y,
+                z,
+                vx: (nx * out - ny * side + bias) * speed,
+                vy: (ny * out + nx * side) * speed,
+                age: 0,
+            });
+        }
+    }
+
+    /// Fly, land on surfaces (maybe joining a stream), evaporate
+    pub fn update(ctx: &mut StepContext) {
+        let (screen_w, screen_h, vp) = (ctx.w as f32, ctx.h as f32, &ctx.viewport);
+        let (pyramid, cfg) = (ctx.pyramid, ctx.config);
+        let rng = &mut *ctx.rng;
+        let Entities { splashes, streams, .. } = &mut *ctx.entities;
+
+        splashes.spray.retain(|e| {
+            e.age += 1;
+            if e.age >= SPRAY_LIFE { return false; }
+
+            let z = e.z;
+            e.vy += GRAVITY * (1.0 - z * 0.6);
+
+            let y_prev = e.y;
+            let x = e.x + e.vx;
+            let y = y_prev + e.vy;
+
+            if x < 0.0 || x >= screen_w || y >= screen_h { return false; }
+
+            // Reached the ground line
+            let ground = cfg.ground(vp, z);
+            if y > ground { return false; }
+
+            // Landed on a surface on the way down
+            if e.vy > 0.0 && y >= 0.0 && vp.bg_y(y) >= 0.0 && e.age > LAUNCH_TICKS {
+                let bx = vp.col(x);
+                let by = vp.row(y);
+                let from = vp.sweep_from(e.x, y_prev, x, y);
+
+                if let Some(row) = pyramid.sweep(bx, from, by, z, cfg.depth_margin) {
+                    if has_flow(bx, row) && RainWorld::rand(rng) < SLIDE_CHANCE * cfg.slide_chance {
+                        streams.spawn(x, y.min(vp.screen_y((row + 1) as f32)), z, cfg);
+                    }
+                    return false;
+                }
+            }
+
+            e.x = x;
+            e.y = y;
+            true
+        });
+    }
+}
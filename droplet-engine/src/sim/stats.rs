@@ -0,0 +1,178 @@
+// stats.rs - Simulation statistics for the debug HUD
+//
+// Counts come from counters the pools keep for the current frame (reset when
+// tick / tick_dt starts and summed over all its steps), so they describe the
+// last frame, however many fixed steps it ran - none included:
+//   - live entities per kind (at the end of the frame)
+//   - spawns, removals and spawns refused by a full pool, per kind
+//   - drop and stream removals by cause; everything not attributed to a
+//     cause (sheltered, blown off screen, faded, melted, ran dry...) is
+//     Other
+//   - drop impacts by the ADE20K class of the pixel hit
+//
+// Timings are wall time per phase (weather and wind, each system's update,
+// render) summed the same way. They're only measured while profiling is on.
+//
+// The wasm API exports it all as one flat Float32Array; see Stats::flatten
+// for the layout.
+
+use super::{Entities, SEGMENT_CLASSES};
+
+/// Entity kinds the stats count
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(u8)]
+pub enum Kind {
+    Drop = 0,
+    Splash = 1,
+    Spray = 2,
+    Stream = 3,
+    Ripple = 4,
+    Flake = 5,
+    Hail = 6,
+}
+
+pub const KINDS: usize = 7;
+pub const KIND_NAMES: [&str; KINDS] = ["drops", "splashes", "spray", "streams", "ripples", "flakes", "hail"];
+
+/// Why a drop or stream was removed
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(u8)]
+pub enum Cause {
+    Ground = 0,      // drop reached the ground line
+    Surface = 1,     // drop hit a surface
+    StreamFall = 2,  // stream slid off its surface
+    Pool = 3,        // stream reached a pool (flow stopped)
+    Other = 4,       // any other removal, of any kind (streams running dry too)
+}
+
+pub const CAUSES: usize = 5;
+pub const CAUSE_NAMES: [&str; CAUSES] = ["ground", "surface", "stream_fall", "pool", "other"];
+
+/// What happened in the last frame (and how long it took)
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stats {
+    pub live: [u32; KINDS],
+    pub spawned: [u32; KINDS],
+    pub died: [u32; KINDS],
+    pub refused: [u32; KINDS],                // pool full
+    pub causes: [u32; CAUSES],                // removals, all kinds
+    pub impacts: [u32; SEGMENT_CLASSES],      // drop hits by segment class
+    pub phases: Vec<(&'static str, f32)>,     // ms, empty unless profiling
+}
+
+impl Stats {
+    // Offsets into the flat array
+    pub const LIVE: usize = 0;
+    pub const SPAWNED: usize = Self::LIVE + KINDS;
+    pub const DIED: usize = Self::SPAWNED + KINDS;
+    pub const REFUSED: usize = Self::DIED + KINDS;
+    pub const CAUSES: usize = Self::REFUSED + KINDS;
+    pub const IMPACTS: usize = Self::CAUSES + CAUSES;
+    pub const PHASES: usize = Self::IMPACTS + SEGMENT_CLASSES;
+
+    pub fn collect(e: &Entities, phases: Vec<(&'static str, f32)>) -> Self {
+        // (live, spawned, died, refused) per kind, in Kind order
+        macro_rules! counts {
+            ($($pool:expr),*) => { [$( [$pool.n as u32, $pool.spawned, $pool.died, $pool.refused] ),*] };
+        }
+        let pools = counts!(e.drops, e.splashes, e.splashes.spray, e.streams, e.ripples, e.flakes, e.hail);
+        let column = |i: usize| pools.map(|p| p[i]);
+
+        let died = column(2);
+        let mut causes = [0; CAUSES];
+        causes[Cause::Ground as usize] = e.drops.ground_hits;
+        causes[Cause::Surface as usize] = e.drops.surface_hits;
+        causes[Cause::StreamFall as usize] = e.streams.fell_off;
+        causes[Cause::Pool as usize] = e.streams.pooled;
+        let attributed: u32 = causes.iter().sum();
+        causes[Cause::Other as usize] = died.iter().sum::<u32>().saturating_sub(attributed);
+
+        Self {
+            live: column(0),
+            spawned: column(1),
+            died,
+            refused: column(3),
+            causes,
+            impacts: e.drops.impacts,
+            phases,
+        }
+    }
+
+    /// Everything as one array: live, spawned, died and refused (KINDS
+    /// each, in Kind order), causes (CAUSES), impacts (SEGMENT_CLASSES),
+    /// then one ms value per phase - see the offset constants
+    pub fn flatten(&self) -> Vec<f32> {
+        let mut out = Vec::with_capacity(Self::PHASES + self.phases.len());
+        for counts in [&self.live[..], &self.spawned, &self.died, &self.refused, &self.causes, &self.impacts] {
+            out.extend(counts.iter().map(|&v| v as f32));
+        }
+        out.extend(self.phases.iter().map(|&(_, ms)| ms));
+        out
+    }
+}
+
+/// Per-phase wall time over a frame (measured only while enabled)
+#[derive(Default)]
+pub(crate) struct Profiler {
+    enabled: bool,
+    ms: Vec<f32>,
+    last: f64,
+}
+
+impl Profiler {
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.ms.clear();
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Start a frame with `phases` zeroed slots
+    pub fn begin(&mut self, phases: usize) {
+        if !self.enabled { return; }
+        self.ms.clear();
+        self.ms.resize(phases, 0.0);
+        self.last = now_ms();
+    }
+
+    /// Charge the time since the last lap to a phase
+    #[inline]
+    pub fn lap(&mut self, phase: usize) {
+        if !self.enabled { return; }
+        let now = now_ms();
+        if let Some(ms) = self.ms.get_mut(phase) {
+            *ms += (now - self.last) as f32;
+        }
+        self.last = now;
+    }
+
+    pub fn ms(&self) -> &[f32] {
+        &self.ms
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen::prelude::wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = performance, js_name = now)]
+    fn performance_now() -> f64;
+}
+
+// Milliseconds since an arbitrary start
+#[cfg(target_arch = "wasm32")]
+fn now_ms() -> f64 {
+    performance_now()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn now_ms() -> f64 {
+    use std::sync::OnceLock;
+    use std::time::Instant;
+
+    static START: OnceLock<Instant> = OnceLock::new();
+    START.get_or_init(Instant::now).elapsed().as_secs_f64() * 1000.0
+}
@@ -2,111 +2,117 @@
 //
 // Water particles that slide along surfaces following the flow field.
 
-use super::{MAX_STREAMS, Splashes};
-use crate::world::{get_flow, has_flow, hits_surface};
+use super::{soa_pool, STEP, Cause, Entities, Impact, SimConfig, StepContext};
+use crate::world::{get_flow_bilinear, get_material, get_normal_bilinear, get_segment, has_flow, hits_surface};
 
-const FLOW_SPEED: f32 = 0.4;
-const FLOW_LIFETIME: u8 = 120;
-const DEPTH_MARGIN: u8 = 48;
+const ABSORB_DRAIN: f32 = 4.0;        // extra life lost per tick at absorption 1.0
+const WIND_PUSH: f32 = 0.1;           // fraction of wind felt by surface water
 
-pub struct Streams {
-    // Position
-    pub x: [f32; MAX_STREAMS],
-    pub y: [f32; MAX_STREAMS],
-    pub z: [f32; MAX_STREAMS],
+soa_pool! {
+    pub struct Streams / Stream {
+        // Position
+        x: f32,
+        y: f32,
+        z: f32,
 
-    // Lifetime
-    pub life: [u8; MAX_STREAMS],
+        // Last step's move (for interpolation)
+        vx: f32,
+        vy: f32,
 
-    // Count
-    pub n: usize,
-}
-
-impl Streams {
-    pub fn new() -> Self {
-        Self {
-            x: [0.0; MAX_STREAMS],
-            y: [0.0; MAX_STREAMS],
-            z: [0.0; MAX_STREAMS],
-            life: [0; MAX_STREAMS],
-            n: 0,
-        }
+        // Lifetime
+        life: u8,
     }
-
-    pub fn clear(&mut self) {
-        self.n = 0;
+    extra {
+        // This frame's streams that fell off a surface, or reached a pool
+        // (for the stats)
+        pub fell_off: u32 = 0,
+        pub pooled: u32 = 0,
     }
+}
 
+impl Streams {
     /// Spawn a new stream particle
-    pub fn spawn(&mut self, x: f32, y: f32, z: f32) {
-        if self.n >= MAX_STREAMS { return; }
-
-        let i = self.n;
-        self.x[i] = x;
-        self.y[i] = y;
-        self.z[i] = z;
-        self.life[i] = FLOW_LIFETIME;
-        self.n += 1;
+    pub fn spawn(&mut self, x: f32, y: f32, z: f32, cfg: &SimConfig) {
+        self.push(Stream { x, y, z, vx: 0.0, vy: 0.0, life: cfg.flow_ticks() });
     }
 
-    /// Move streams along flow field
-    pub fn update(
-        &mut self,
-        screen_w: f32,
-        screen_h: f32,
-        scale_x: f32,
-        scale_y: f32,
-        splashes: &mut Splashes,
-    ) {
-        let mut rng = 0x12345678u32; // Local RNG for splashes
-        let mut write = 0;
-
-        for read in 0..self.n {
-            let life = self.life[read];
-            if life == 0 { continue; }
-
-            let x = self.x[read];
-            let y = self.y[read];
-            let z = self.z[read];
-
-            // Get flow at current position
-            let bx = (x * scale_x) as usize;
-            let by = (y * scale_y) as usize;
-            let (fx, fy) = get_flow(bx, by);
+    /// Move streams along flow field (run-off splashes draw from the stream
+    /// system's RNG)
+    pub fn update(ctx: &mut StepContext) {
+        let (screen_w, screen_h, vp) = (ctx.w as f32, ctx.h as f32, &ctx.viewport);
+        let (wind, cfg) = (ctx.wind, ctx.config);
+        let rng = &mut *ctx.rng;
+        let Entities { streams, splashes, ripples, puddles, impacts: events, .. } = &mut *ctx.entities;
+
+        let lifetime = cfg.flow_ticks();
+        let (mut fell_off, mut pooled) = (0, 0);
+
+        streams.retain(|e| {
+            let (x, y, z) = (e.x, e.y, e.z);
+
+            // Background position
+            let bx = vp.col(x);
+            let by = vp.row(y);
+            let event = |kind| {
+                let normal = get_normal_bilinear(vp.bg_x(x), vp.bg_y(y));
+                Impact::new(x, y, z, normal, get_segment(bx, by), kind)
+            };
+
+            // Ran out - leave the water where it stopped (not a pool it
+            // reached, so it's an Other removal)
+            if e.life == 0 {
+                puddles.deposit(bx, by);
+                events.push(|| event(Cause::Other));
+                return false;
+            }
+
+            // Get flow at current position (sub-pixel, so slow streams glide)
+            let (fx, fy) = get_flow_bilinear(vp.bg_x(x), vp.bg_y(y));
 
             // Move along flow (slower when far for perspective)
-            let speed = FLOW_SPEED * (1.0 - z * 0.5);
-            let new_x = x + fx * speed;
+            let speed = cfg.flow_speed * STEP * (1.0 - z * 0.5);
+            let new_x = x + fx * speed + wind.at(z) * WIND_PUSH;
             let new_y = y + fy * speed;
 
             // Check bounds
             if new_x < 0.0 || new_x >= screen_w || new_y < 0.0 || new_y >= screen_h {
-                continue;
+                return false;
             }
 
             // Check if still on surface (reuse bx/by for nearby position - close enough)
-            if !hits_surface(bx, by, z, DEPTH_MARGIN) {
+            if !hits_surface(bx, by, z, cfg.depth_margin) {
                 // Fell off - splash
-                if life > 60 {
-                    splashes.spawn(x, y, z, 2, &mut rng);
+                if e.life > lifetime / 2 {
+                    splashes.spawn(x, y, z, 2, rng);
                 }
-                continue;
+                fell_off += 1;
+                events.push(|| event(Cause::StreamFall));
+                return false;
             }
 
             // Check if flow stopped (reached pool)
             if !has_flow(bx, by) {
-                splashes.spawn(new_x, new_y, z, 0, &mut rng);
-                continue;
+                puddles.deposit(bx, by);
+                ripples.spawn(new_x, new_y, z);
+                pooled += 1;
+                events.push(|| event(Cause::Pool));
+                return false;
             }
 
+            // Absorbent materials soak the stream up faster
+            let absorption = get_material(bx, by).props().absorption;
+            let drain = 1 + (absorption * ABSORB_DRAIN) as u8;
+
             // Keep sliding
-            self.x[write] = new_x;
-            self.y[write] = new_y;
-            self.z[write] = z;
-            self.life[write] = life - 1;
-            write += 1;
-        }
-
-        self.n = write;
+            e.vx = new_x - x;
+            e.vy = new_y - y;
+            e.x = new_x;
+            e.y = new_y;
+            e.life = e.life.saturating_sub(drain);
+            true
+        });
+
+        streams.fell_off += fell_off;
+        streams.pooled += pooled;
     }
 }
@@ -0,0 +1,293 @@
+// system.rs - Pluggable simulation systems
+//
+// A step runs every registered system's update in order; a render runs
+// their render passes in the same order. The built-in entities are systems
+// too (see builtin.rs), registered at spaced-out order keys so custom ones
+// can slot in between - e.g. leaves riding the streams go after
+// ORDER_STREAMS so they see this step's stream positions.
+//
+// All entity storage lives in Entities, shared by every system: any system
+// can spawn into the built-in pools, and custom pools registered there are
+// reachable by type, so custom systems can spawn into each other's too.
+//
+// Each system draws from its own RNG stream split off the world seed (see
+// rng.rs), so adding a system doesn't shift the randomness of the others.
+//
+// Snapshots (snapshot.rs) store each system's RNG state under its name,
+// plus whatever the system writes in save() - custom pools aren't known to
+// the snapshot otherwise.
+
+use std::any::Any;
+
+use super::{rng, Droplets, Splashes, Streams, Ripples, Flakes, Hail, Lightning, Puddles, Drips, Snow, Impacts};
+use super::{Precipitation, SEGMENT_CLASSES, SimConfig, SnapReader, SnapWriter, Viewport, Weather, Wind};
+use crate::render::Encoder;
+use crate::world::DepthPyramid;
+
+// Order keys of the built-in systems (lower runs first)
+pub const ORDER_LIGHTNING: i32 = 100;
+pub const ORDER_SPAWN: i32 = 200;
+pub const ORDER_DROPS: i32 = 300;
+pub const ORDER_SPLASHES: i32 = 400;
+pub const ORDER_STREAMS: i32 = 500;
+pub const ORDER_RIPPLES: i32 = 600;
+pub const ORDER_FLAKES: i32 = 700;
+pub const ORDER_HAIL: i32 = 800;
+pub const ORDER_PUDDLES: i32 = 900;
+pub const ORDER_SNOW: i32 = 1000;  // renders only into empty cells, keep it last
+
+/// A simulation system: advances some entities each step and encodes them
+pub trait System {
+    /// Unique name (also keys the system's RNG stream)
+    fn name(&self) -> &'static str;
+
+    /// Advance one fixed step
+    fn update(&mut self, _ctx: &mut StepContext) {}
+
+    /// Encode to the output buffer
+    fn render(&self, _ctx: &RenderContext, _encoder: &mut Encoder) {}
+
+    /// The screen or viewport changed: move live entities so they keep
+    /// their place in the scene (screen -> background through `from`, back
+    /// through `to`). Systems without screen-space state can skip it.
+    fn rescale(&mut self, _entities: &mut Entities, _from: &Viewport, _to: &Viewport) {}
+
+    /// Drop live entities (resize with reset_on_resize set)
+    fn reset(&mut self, _entities: &mut Entities) {}
+
+    /// Write state the snapshot doesn't cover (own fields, custom pools)
+    fn save(&self, _entities: &Entities, _w: &mut SnapWriter) {}
+
+    /// Read back what save wrote
+    fn load(&mut self, _entities: &mut Entities, _r: &mut SnapReader) -> Result<(), &'static str> {
+        Ok(())
+    }
+}
+
+/// Entity storage shared by all systems
+pub struct Entities {
+    pub drops: Droplets,
+    pub splashes: Splashes,
+    pub streams: Streams,
+    pub ripples: Ripples,
+    pub flakes: Flakes,
+    pub hail: Hail,
+    pub lightning: Lightning,
+
+    // Dynamic layers (background resolution)
+    pub puddles: Puddles,
+    pub drips: Drips,
+    pub snow: Snow,
+
+    // Impact events of the current frame (see impact.rs)
+    pub impacts: Impacts,
+
+    // Pools added by custom systems, one per type
+    custom: Vec<Box<dyn Any>>,
+}
+
+impl Entities {
+    pub fn new(drops: usize, splashes: usize, streams: usize, ripples: usize, flakes: usize, hail: usize) -> Self {
+        Self {
+            drops: Droplets::new(drops),
+            splashes: Splashes::new(splashes),
+            streams: Streams::new(streams),
+            ripples: Ripples::new(ripples),
+            flakes: Flakes::new(flakes),
+            hail: Hail::new(hail),
+            lightning: Lightning::new(),
+            puddles: Puddles::new(),
+            drips: Drips::new(),
+            snow: Snow::new(),
+            impacts: Impacts::new(),
+            custom: Vec::new(),
+        }
+    }
+
+    /// Add a custom pool (replaces one of the same type)
+    pub fn insert<T: Any>(&mut self, pool: T) {
+        match self.custom.iter_mut().find(|p| p.is::<T>()) {
+            Some(p) => *p = Box::new(pool),
+            None => self.custom.push(Box::new(pool)),
+        }
+    }
+
+    pub fn get<T: Any>(&self) -> Option<&T> {
+        self.custom.iter().find_map(|p| p.downcast_ref())
+    }
+
+    pub fn get_mut<T: Any>(&mut self) -> Option<&mut T> {
+        self.custom.iter_mut().find_map(|p| p.downcast_mut())
+    }
+
+    /// Zero the per-frame counters the stats read (see stats.rs)
+    pub fn reset_counts(&mut self) {
+        self.drops.reset_counts();
+        self.drops.ground_hits = 0;
+        self.drops.surface_hits = 0;
+        self.drops.impacts = [0; SEGMENT_CLASSES];
+        self.splashes.reset_counts();
+        self.splashes.spray.reset_counts();
+        self.streams.reset_counts();
+        self.streams.fell_off = 0;
+        self.streams.pooled = 0;
+        self.ripples.reset_counts();
+        self.flakes.reset_counts();
+        self.hail.reset_counts();
+    }
+
+    /// Move the custom pools over from another store
+    pub(crate) fn take_custom(&mut self, from: &mut Entities) {
+        self.custom = std::mem::take(&mut from.custom);
+    }
+}
+
+/// What a system sees during a step
+pub struct StepContext<'a> {
+    // Screen size and screen <-> background mapping (for the world:: queries)
+    pub w: u32,
+    pub h: u32,
+    pub viewport: Viewport,
+
+    // Environment (already advanced this step)
+    pub config: &'a SimConfig,
+    pub wind: &'a Wind,
+    pub weather: &'a Weather,
+    pub precipitation: Precipitation,
+    pub spawn_count: usize,  // precipitation the weather asks for this step
+
+    pub pyramid: &'a DepthPyramid,
+    pub entities: &'a mut Entities,
+
+    // This system's random stream (use with RainWorld::rand)
+    pub rng: &'a mut u32,
+}
+
+/// What a system sees while rendering
+pub struct RenderContext<'a> {
+    pub w: u32,
+    pub h: u32,
+    pub viewport: Viewport,
+
+    // How far into the next step this frame is (see render.rs)
+    pub alpha: f32,
+
+    pub entities: &'a Entities,
+}
+
+struct Entry {
+    order: i32,
+    stream: u32,
+    rng: u32,
+    system: Box<dyn System>,
+}
+
+/// Registered systems, kept sorted by order key (ties run in the order
+/// they were added)
+pub struct Systems {
+    entries: Vec<Entry>,
+}
+
+impl Systems {
+    pub fn new() -> Self {
+        Self { entries: Vec::new() }
+    }
+
+    /// Register a system whose RNG stream is keyed by its name
+    pub fn add(&mut self, order: i32, system: Box<dyn System>, seed: u32) -> Result<(), &'static str> {
+        let stream = rng::named(system.name());
+        self.add_with_stream(order, stream, system, seed)
+    }
+
+    /// Register a system on a fixed stream id (the built-ins keep theirs)
+    pub fn add_with_stream(&mut self, order: i32, stream: u32, system: Box<dyn System>, seed: u32) -> Result<(), &'static str> {
+        if self.contains(system.name()) { return Err("system name already registered"); }
+
+        let at = self.entries.iter().position(|e| e.order > order).unwrap_or(self.entries.len());
+        self.entries.insert(at, Entry { order, stream, rng: rng::stream(seed, stream), system });
+        Ok(())
+    }
+
+    /// Unregister a system (built-ins included); false if there was none
+    pub fn remove(&mut self, name: &str) -> bool {
+        let before = self.entries.len();
+        self.entries.retain(|e| e.system.name() != name);
+        self.entries.len() != before
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.entries.iter().any(|e| e.system.name() == name)
+    }
+
+    /// (name, order) of every system, in run order
+    pub fn list(&self) -> impl Iterator<Item = (&'static str, i32)> + '_ {
+        self.entries.iter().map(|e| (e.system.name(), e.order))
+    }
+
+    pub fn reseed(&mut self, seed: u32) {
+        for e in &mut self.entries {
+            e.rng = rng::stream(seed, e.stream);
+        }
+    }
+
+    /// Each system with its RNG state, in run order
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&mut (dyn System + 'static), &mut u32)> + '_ {
+        self.entries.iter_mut().map(|e| (e.system.as_mut(), &mut e.rng))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn System> + '_ {
+        self.entries.iter().map(|e| e.system.as_ref())
+    }
+
+    /// Name, RNG state and a length-prefixed save() section per system
+    pub fn save(&self, entities: &Entities, w: &mut SnapWriter) {
+        w.u32(self.entries.len() as u32);
+        for e in &self.entries {
+            let mut section = SnapWriter::new();
+            e.system.save(entities, &mut section);
+
+            w.str(e.system.name());
+            w.u32(e.rng);
+            w.bytes(&section.into_bytes());
+        }
+    }
+
+    /// Walk the sections written by save() without applying them
+    pub fn check(&self, r: &mut SnapReader) -> Result<(), &'static str> {
+        for _ in 0..r.u32()? {
+            r.str()?;
+            r.rng()?;
+            r.bytes()?;
+        }
+        Ok(())
+    }
+
+    /// Restore the systems found by name; others are left as they are
+    pub fn load(&mut self, entities: &mut Entities, r: &mut SnapReader) -> Result<(), &'static str> {
+        for _ in 0..r.u32()? {
+            let name = r.str()?;
+            let rng = r.rng()?;
+            let section = r.bytes()?;
+
+            if let Some(e) = self.entries.iter_mut().find(|e| e.system.name() == name) {
+                e.rng = rng;
+                e.system.load(entities, &mut SnapReader::new(section))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for Systems {
+    fn default() -> Self {
+        Self::new()
+    }
+}
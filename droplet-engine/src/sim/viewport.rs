@@ -0,0 +1,181 @@
+// viewport.rs - Screen <-> background mapping
+//
+// Where the scene sits on screen, like CSS object-fit:
+//   stretch  - fill the screen, each axis scaled on its own (distorts)
+//   cover    - keep the aspect ratio and fill, cropping the overflow
+//   contain  - keep the aspect ratio and fit, leaving letterbox bands
+// The anchor places the scene when it doesn't match the screen (0 = left /
+// top, 0.5 = centred, 1 = right / bottom), like object-position.
+//
+// Screen cells needn't be square, so keeping the aspect ratio needs the
+// cell's width / height in pixels.
+//
+// Every screen <-> background conversion goes through here, and rect() is
+// exported so the front-end draws the background in the same place.
+// Off-scene positions (letterbox bands) map to column BG_WIDTH / row
+// BG_HEIGHT, which every world query treats as outside the scene.
+
+use crate::scene::{BG_WIDTH, BG_HEIGHT};
+
+/// How the scene is fitted to the screen
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(u8)]
+pub enum ViewportMode {
+    Stretch = 0,
+    Cover = 1,
+    Contain = 2,
+}
+
+impl ViewportMode {
+    pub fn from_u8(v: u8) -> Option<Self> {
+        match v {
+            0 => Some(Self::Stretch),
+            1 => Some(Self::Cover),
+            2 => Some(Self::Contain),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Viewport {
+    // Fit settings
+    mode: ViewportMode,
+    anchor_x: f32,
+    anchor_y: f32,
+    cell_aspect: f32,  // cell width / height in pixels
+
+    // Scene rectangle in screen cells
+    pub left: f32,
+    pub top: f32,
+    pub width: f32,
+    pub height: f32,
+
+    // Background pixels per screen cell
+    pub scale_x: f32,
+    pub scale_y: f32,
+}
+
+impl Viewport {
+    pub const DEFAULT_CELL_ASPECT: f32 = 0.5;  // 8 x 16 px cells
+
+    /// Stretched to a w x h screen (the default)
+    pub fn new(w: u32, h: u32) -> Self {
+        let mut vp = Self {
+            mode: ViewportMode::Stretch,
+            anchor_x: 0.5,
+            anchor_y: 0.5,
+            cell_aspect: Self::DEFAULT_CELL_ASPECT,
+            left: 0.0,
+            top: 0.0,
+            width: 0.0,
+            height: 0.0,
+            scale_x: 0.0,
+            scale_y: 0.0,
+        };
+        vp.fit(w, h);
+        vp
+    }
+
+    /// Change the fit; anchors are clamped to 0-1
+    pub fn set_mode(&mut self, mode: ViewportMode, anchor_x: f32, anchor_y: f32, w: u32, h: u32) {
+        self.mode = mode;
+        self.anchor_x = anchor_x.clamp(0.0, 1.0);
+        self.anchor_y = anchor_y.clamp(0.0, 1.0);
+        self.fit(w, h);
+    }
+
+    /// Cell width / height in pixels (ignored by stretch)
+    pub fn set_cell_aspect(&mut self, aspect: f32, w: u32, h: u32) {
+        if aspect.is_finite() && aspect > 0.0 {
+            self.cell_aspect = aspect;
+        }
+        self.fit(w, h);
+    }
+
+    /// Recompute the scene rectangle for a w x h screen
+    pub fn fit(&mut self, w: u32, h: u32) {
+        let (w, h) = (w as f32, h as f32);
+        let (bg_w, bg_h) = (BG_WIDTH as f32, BG_HEIGHT as f32);
+
+        let (width, height) = match self.mode {
+            ViewportMode::Stretch => (w, h),
+            ViewportMode::Cover | ViewportMode::Contain => {
+                // Pixels per background pixel that fill each axis (cell height = 1)
+                let kx = w * self.cell_aspect / bg_w;
+                let ky = h / bg_h;
+                let k = if self.mode == ViewportMode::Cover { kx.max(ky) } else { kx.min(ky) };
+                (bg_w * k / self.cell_aspect, bg_h * k)
+            }
+        };
+
+        self.left = (w - width) * self.anchor_x;
+        self.top = (h - height) * self.anchor_y;
+        self.width = width;
+        self.height = height;
+        self.scale_x = bg_w / width;
+        self.scale_y = bg_h / height;
+    }
+
+    pub fn mode(&self) -> ViewportMode { self.mode }
+    pub fn anchor(&self) -> (f32, f32) { (self.anchor_x, self.anchor_y) }
+    pub fn cell_aspect(&self) -> f32 { self.cell_aspect }
+
+    /// Scene rectangle in screen cells: [left, top, width, height]
+    pub fn rect(&self) -> [f32; 4] {
+        [self.left, self.top, self.width, self.height]
+    }
+
+    /// Background x under screen x (continuous, may be off the scene)
+    #[inline(always)]
+    pub fn bg_x(&self, x: f32) -> f32 {
+        (x - self.left) * self.scale_x
+    }
+
+    #[inline(always)]
+    pub fn bg_y(&self, y: f32) -> f32 {
+        (y - self.top) * self.scale_y
+    }
+
+    /// Background column under screen x (BG_WIDTH or more = off the scene)
+    #[inline(always)]
+    pub fn col(&self, x: f32) -> usize {
+        let b = self.bg_x(x);
+        if b < 0.0 { BG_WIDTH } else { b as usize }
+    }
+
+    /// Background row under screen y (BG_HEIGHT or more = off the scene)
+    #[inline(always)]
+    pub fn row(&self, y: f32) -> usize {
+        let b = self.bg_y(y);
+        if b < 0.0 { BG_HEIGHT } else { b as usize }
+    }
+
+    /// First background row to sweep for something that moved from screen
+    /// (x_prev, y_prev) to (x, y) this step: the row after the old one, or
+    /// the old row itself if it changed column (that pixel was never tested
+    /// in the new one), or the top visible row if it came from above the
+    /// screen (cropped rows never collide). Never past the new row, so a
+    /// drop that stays in its row still tests where it is
+    #[inline(always)]
+    pub fn sweep_from(&self, x_prev: f32, y_prev: f32, x: f32, y: f32) -> usize {
+        let from = if y_prev < 0.0 {
+            self.bg_y(0.0).max(0.0) as usize
+        } else {
+            let b = self.bg_y(y_prev);
+            if b < 0.0 { 0 } else { b as usize + (self.col(x_prev) == self.col(x)) as usize }
+        };
+        from.min(self.row(y))
+    }
+
+    /// Screen x of background x
+    #[inline(always)]
+    pub fn screen_x(&self, bx: f32) -> f32 {
+        bx / self.scale_x + self.left
+    }
+
+    #[inline(always)]
+    pub fn screen_y(&self, by: f32) -> f32 {
+        by / self.scale_y + self.top
+    }
+}
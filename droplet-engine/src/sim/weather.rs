@@ -0,0 +1,232 @@
+// weather.rs - Weather states with eased transitions
+//
+// Each state sets spawn rate, drop speed, wind and splash probability.
+// Changing state blends from the current mix to the new one. Auto mode
+// wanders between neighbouring states on its own, using the weather RNG
+// stream so it plays out the same way for a given seed.
+
+use super::{rng, RainWorld, SnapReader, SnapWriter, ticks};
+
+const DEFAULT_TRANSITION: u32 = ticks(10.0);
+const AUTO_HOLD_MIN: u32 = ticks(30.0);
+const AUTO_HOLD_MAX: u32 = ticks(120.0);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(u8)]
+pub enum WeatherState {
+    Clear = 0,
+    Drizzle = 1,
+    Rain = 2,
+    Downpour = 3,
+    Storm = 4,
+}
+
+impl WeatherState {
+    pub fn from_u8(v: u8) -> Option<Self> {
+        match v {
+            0 => Some(Self::Clear),
+            1 => Some(Self::Drizzle),
+            2 => Some(Self::Rain),
+            3 => Some(Self::Downpour),
+            4 => Some(Self::Storm),
+            _ => None,
+        }
+    }
+}
+
+/// What a weather state drives (blended during transitions)
+#[derive(Clone, Copy, Debug)]
+pub struct WeatherParams {
+    pub spawn_rate: f32,    // x baseline drops per tick
+    pub vel_scale: f32,     // x drop fall speed
+    pub vel_spread: f32,    // +/- random speed variation
+    pub wind: f32,          // added to base wind (cells/s)
+    pub splash_scale: f32,  // x material splash chance
+    pub intensity: f32,     // 0 = clear, 1 = storm
+}
+
+impl WeatherParams {
+    fn lerp(a: &Self, b: &Self, t: f32) -> Self {
+        let l = |x: f32, y: f32| x + (y - x) * t;
+        Self {
+            spawn_rate: l(a.spawn_rate, b.spawn_rate),
+            vel_scale: l(a.vel_scale, b.vel_scale),
+            vel_spread: l(a.vel_spread, b.vel_spread),
+            wind: l(a.wind, b.wind),
+            splash_scale: l(a.splash_scale, b.splash_scale),
+            intensity: l(a.intensity, b.intensity),
+        }
+    }
+
+    fn fields(&self) -> [f32; 6] {
+        [self.spawn_rate, self.vel_scale, self.vel_spread, self.wind, self.splash_scale, self.intensity]
+    }
+
+    fn save(&self, w: &mut SnapWriter) {
+        for v in self.fields() {
+            w.f32(v);
+        }
+    }
+
+    // Blends of the states never leave their range (1% slack for rounding)
+    fn load(r: &mut SnapReader) -> Result<Self, &'static str> {
+        let mut v = [0.0; 6];
+        for (i, v) in v.iter_mut().enumerate() {
+            let max = STATES.iter().map(|s| s.fields()[i]).fold(0.0, f32::max);
+            *v = r.f32_in(0.0..=max * 1.01)?;
+        }
+        let [spawn_rate, vel_scale, vel_spread, wind, splash_scale, intensity] = v;
+        Ok(Self { spawn_rate, vel_scale, vel_spread, wind, splash_scale, intensity })
+    }
+}
+
+// Indexed by WeatherState as usize. Rain is the original fixed behaviour.
+static STATES: [WeatherParams; 5] = [
+    // Clear
+    WeatherParams { spawn_rate: 0.0, vel_scale: 1.0, vel_spread: 0.2, wind: 0.0, splash_scale: 1.0, intensity: 0.0 },
+    // Drizzle
+    WeatherParams { spawn_rate: 0.3, vel_scale: 0.7, vel_spread: 0.1, wind: 0.0, splash_scale: 0.4, intensity: 0.25 },
+    // Rain
+    WeatherParams { spawn_rate: 1.0, vel_scale: 1.0, vel_spread: 0.2, wind: 0.0, splash_scale: 1.0, intensity: 0.5 },
+    // Downpour
+    WeatherParams { spawn_rate: 2.0, vel_scale: 1.2, vel_spread: 0.25, wind: 9.0, splash_scale: 1.0, intensity: 0.75 },
+    // Storm
+    WeatherParams { spawn_rate: 3.0, vel_scale: 1.4, vel_spread: 0.3, wind: 30.0, splash_scale: 1.0, intensity: 1.0 },
+];
+
+pub struct Weather {
+    // Transition
+    from: WeatherParams,
+    target: WeatherState,
+    elapsed: u32,
+    duration: u32,
+
+    // Blended result this tick
+    current: WeatherParams,
+
+    // Fractional drops carried between ticks
+    spawn_acc: f32,
+
+    // Auto mode
+    auto: bool,
+    hold: u32,
+    rng: u32,
+}
+
+impl Weather {
+    pub fn new() -> Self {
+        let rain = STATES[WeatherState::Rain as usize];
+        Self {
+            from: rain,
+            target: WeatherState::Rain,
+            elapsed: 0,
+            duration: 0,
+            current: rain,
+            spawn_acc: 0.0,
+            auto: false,
+            hold: AUTO_HOLD_MIN,
+            rng: rng::stream(rng::DEFAULT_SEED, rng::WEATHER),
+        }
+    }
+
+    /// Start blending toward a state over `ticks` (0 = instant)
+    pub fn set_state(&mut self, state: WeatherState, ticks: u32) {
+        self.from = self.current;
+        self.target = state;
+        self.elapsed = 0;
+        self.duration = ticks;
+        if ticks == 0 {
+            self.current = STATES[state as usize];
+        }
+    }
+
+    /// Restart the auto mode RNG
+    pub fn seed(&mut self, state: u32) {
+        self.rng = state;
+    }
+
+    /// Let the weather wander on its own
+    pub fn set_auto(&mut self, auto: bool) {
+        self.auto = auto;
+    }
+
+    pub fn state(&self) -> WeatherState { self.target }
+    pub fn params(&self) -> &WeatherParams { &self.current }
+    pub fn intensity(&self) -> f32 { self.current.intensity }
+
+    /// Advance transition (and auto mode) one tick
+    pub fn update(&mut self) {
+        if self.auto {
+            self.hold = self.hold.saturating_sub(1);
+            if self.hold == 0 {
+                self.wander();
+            }
+        }
+
+        if self.elapsed < self.duration {
+            self.elapsed += 1;
+            let s = self.elapsed as f32 / self.duration as f32;
+            let e = s * s * (3.0 - 2.0 * s);  // smoothstep
+            self.current = WeatherParams::lerp(&self.from, &STATES[self.target as usize], e);
+        }
+    }
+
+    /// Drops to spawn this tick for a given baseline
+    pub fn spawn_count(&mut self, baseline: usize) -> usize {
+        self.spawn_acc += baseline as f32 * self.current.spawn_rate;
+        let n = self.spawn_acc as usize;
+        self.spawn_acc -= n as f32;
+        n
+    }
+
+    // Step to a neighbouring state (or stay), then hold for a while
+    fn wander(&mut self) {
+        let r = RainWorld::rand(&mut self.rng);
+        let cur = self.target as u8;
+        let next = if r < 0.4 {
+            cur.saturating_sub(1)
+        } else if r < 0.8 {
+            (cur + 1).min(WeatherState::Storm as u8)
+        } else {
+            cur
+        };
+
+        if let Some(state) = WeatherState::from_u8(next).filter(|&s| s != self.target) {
+            self.set_state(state, DEFAULT_TRANSITION);
+        }
+
+        let span = (AUTO_HOLD_MAX - AUTO_HOLD_MIN) as f32;
+        self.hold = AUTO_HOLD_MIN + (RainWorld::rand(&mut self.rng) * span) as u32;
+    }
+
+    pub fn save(&self, w: &mut SnapWriter) {
+        self.from.save(w);
+        w.u8(self.target as u8);
+        w.u32(self.elapsed);
+        w.u32(self.duration);
+        self.current.save(w);
+        w.f32(self.spawn_acc);
+        w.bool(self.auto);
+        w.u32(self.hold);
+        w.u32(self.rng);
+    }
+
+    pub fn load(&mut self, r: &mut SnapReader) -> Result<(), &'static str> {
+        self.from = WeatherParams::load(r)?;
+        self.target = WeatherState::from_u8(r.u8()?).ok_or("snapshot is corrupt")?;
+        self.elapsed = r.u32()?;
+        self.duration = r.u32()?;
+        self.current = WeatherParams::load(r)?;
+        self.spawn_acc = r.f32_in(0.0..=1.0)?;
+        self.auto = r.bool()?;
+        self.hold = r.u32()?;
+        self.rng = r.rng()?;
+        Ok(())
+    }
+}
+
+impl Default for Weather {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,126 @@
+// wind.rs - Global wind with gusts
+//
+// Wind is a horizontal velocity (+ = right), set in screen cells per second
+// and applied as cells per tick.
+// Slow value noise modulates the base strength to make gusts; far drops
+// drift more than near ones.
+
+use super::{rng, RainWorld, SnapReader, SnapWriter, STEP, ticks};
+
+const GUST_PERIOD: u32 = ticks(3.0);  // ticks between gust targets
+const GUST_AMOUNT: f32 = 0.5;         // gusts swing strength by +/-50%
+const DEPTH_DRIFT: f32 = 0.6;         // far drops (z=1) drift 60% more
+
+const MAX_WIND: f32 = 1000.0;         // strength limit either way (cells/s)
+const MAX_DEPTH_DRIFT: f32 = 10.0;    // ... and extra far drift
+
+pub struct Wind {
+    strength: f32,     // base strength (cells/s)
+    boost: f32,        // added by weather (cells/s)
+    depth_drift: f32,  // extra drift at z=1 (0 = same at all depths)
+
+    // Gust noise: eased between random targets in [-1, 1]
+    gust_from: f32,
+    gust_to: f32,
+    t: u32,
+
+    current: f32,      // strength including gust, cells per tick
+
+    rng: u32,
+}
+
+impl Wind {
+    pub fn new() -> Self {
+        Self {
+            strength: 0.0,
+            boost: 0.0,
+            depth_drift: DEPTH_DRIFT,
+            gust_from: 0.0,
+            gust_to: 0.0,
+            t: 0,
+            current: 0.0,
+            rng: rng::stream(rng::DEFAULT_SEED, rng::WIND),
+        }
+    }
+
+    pub fn set_strength(&mut self, strength: f32) {
+        if strength.is_finite() {
+            self.strength = strength.clamp(-MAX_WIND, MAX_WIND);
+        }
+    }
+
+    /// Extra strength on top of the base (driven by weather)
+    pub fn set_boost(&mut self, boost: f32) {
+        self.boost = boost;
+    }
+
+    pub fn set_depth_drift(&mut self, drift: f32) {
+        if drift.is_finite() {
+            self.depth_drift = drift.clamp(0.0, MAX_DEPTH_DRIFT);
+        }
+    }
+
+    /// Restart the gust RNG
+    pub fn seed(&mut self, state: u32) {
+        self.rng = state;
+    }
+
+    /// Advance gust noise one tick
+    pub fn update(&mut self) {
+        self.t += 1;
+        if self.t >= GUST_PERIOD {
+            self.t = 0;
+            self.gust_from = self.gust_to;
+            self.gust_to = RainWorld::rand(&mut self.rng) * 2.0 - 1.0;
+        }
+
+        // Smoothstep between targets
+        let s = self.t as f32 / GUST_PERIOD as f32;
+        let e = s * s * (3.0 - 2.0 * s);
+        let gust = self.gust_from + (self.gust_to - self.gust_from) * e;
+
+        self.current = (self.strength + self.boost) * (1.0 + GUST_AMOUNT * gust) * STEP;
+    }
+
+    /// Wind this tick (cells per tick)
+    #[inline(always)]
+    pub fn current(&self) -> f32 {
+        self.current
+    }
+
+    /// Wind felt by an entity at depth z
+    #[inline(always)]
+    pub fn at(&self, z: f32) -> f32 {
+        self.current * (1.0 + self.depth_drift * z)
+    }
+
+    pub fn save(&self, w: &mut SnapWriter) {
+        w.f32(self.strength);
+        w.f32(self.boost);
+        w.f32(self.depth_drift);
+        w.f32(self.gust_from);
+        w.f32(self.gust_to);
+        w.u32(self.t);
+        w.f32(self.current);
+        w.u32(self.rng);
+    }
+
+    pub fn load(&mut self, r: &mut SnapReader) -> Result<(), &'static str> {
+        self.strength = r.f32_in(-MAX_WIND..=MAX_WIND)?;
+        self.boost = r.f32_in(-MAX_WIND..=MAX_WIND)?;
+        self.depth_drift = r.f32_in(0.0..=MAX_DEPTH_DRIFT)?;
+        self.gust_from = r.f32_in(-1.0..=1.0)?;
+        self.gust_to = r.f32_in(-1.0..=1.0)?;
+        self.t = r.u32()?;
+        if self.t >= GUST_PERIOD { return Err("snapshot is corrupt"); }
+        self.current = r.f32_in(-MAX_WIND..=MAX_WIND)?;
+        self.rng = r.rng()?;
+        Ok(())
+    }
+}
+
+impl Default for Wind {
+    fn default() -> Self {
+        Self::new()
+    }
+}
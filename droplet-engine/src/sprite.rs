@@ -0,0 +1,200 @@
+// sprite.rs - Splash sprite sheets
+//
+// The keyframed splash patterns as data. A sheet holds sprites; each sprite
+// serves one splash type (its slot) and has frames of cells. Cells are
+// placed relative to the impact, scaled by the depth factor `s`:
+//
+//   x = cx + cell.x * s + cell.drift * dir
+//   y = gy - cell.y * s
+//
+// Several sprites in one slot are variants, picked per splash. The built-in
+// sheet matches the original hand-written patterns; new sheets can be
+// loaded at runtime from the binary format below.
+//
+// Binary format (version 1):
+//   "SPLS"  magic
+//   u8      version (1)
+//   u8      sprite count
+//   per sprite:
+//     u8    slot (0 crown, 1 left, 2 right, 3 spray)
+//     u8    frame count (>= 1)
+//     per frame:
+//       u8  cell count
+//       per cell: i8 x, i8 drift, i8 y, u8 glyph (0-7)
+
+pub const SPRITE_SLOTS: usize = 4;
+
+const MAGIC: &[u8; 4] = b"SPLS";
+const VERSION: u8 = 1;
+const GLYPHS: u8 = 8;
+
+#[derive(Clone, Copy, Debug)]
+pub struct SpriteCell {
+    pub x: i8,      // columns from impact, in units of s
+    pub drift: i8,  // multiple of the splash's drift
+    pub y: i8,      // rows above impact, in units of s
+    pub glyph: u8,  // splash character (0-7)
+}
+
+const fn c(x: i8, drift: i8, y: i8, glyph: u8) -> SpriteCell {
+    SpriteCell { x, drift, y, glyph }
+}
+
+pub struct SpriteSheet {
+    cells: Vec<SpriteCell>,
+    frames: Vec<(u16, u16)>,             // (first cell, cell count)
+    sprites: Vec<(u16, u8)>,             // (first frame, frame count)
+    slots: [Vec<u16>; SPRITE_SLOTS],     // sprite indices per splash type
+}
+
+impl SpriteSheet {
+    /// The original crown / left / right / spray patterns
+    pub fn builtin() -> Self {
+        let mut sheet = Self::empty();
+        for (slot, frames) in [&CROWN, &LEFT, &RIGHT, &SPRAY].into_iter().enumerate() {
+            sheet.begin_sprite(slot as u8);
+            for cells in frames.iter() {
+                sheet.push_frame(cells);
+            }
+        }
+        sheet
+    }
+
+    /// Parse a sheet in the binary format
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        let mut r = Reader { bytes, pos: 0 };
+
+        if r.take(4)? != MAGIC { return Err("not a splash sheet"); }
+        if r.u8()? != VERSION { return Err("unsupported splash sheet version"); }
+
+        let mut sheet = Self::empty();
+        let count = r.u8()?;
+        if count == 0 { return Err("splash sheet has no sprites"); }
+
+        for _ in 0..count {
+            let slot = r.u8()?;
+            if slot as usize >= SPRITE_SLOTS { return Err("sprite slot out of range"); }
+            let frame_count = r.u8()?;
+            if frame_count == 0 { return Err("sprite has no frames"); }
+
+            sheet.begin_sprite(slot);
+            for _ in 0..frame_count {
+                let n = r.u8()? as usize;
+                let raw = r.take(n * 4)?;
+                let cells: Vec<SpriteCell> = raw
+                    .chunks_exact(4)
+                    .map(|b| c(b[0] as i8, b[1] as i8, b[2] as i8, b[3]))
+                    .collect();
+                if cells.iter().any(|cell| cell.glyph >= GLYPHS) {
+                    return Err("sprite glyph out of range");
+                }
+                sheet.push_frame(&cells);
+            }
+        }
+
+        if r.pos != bytes.len() { return Err("trailing bytes after splash sheet"); }
+        Ok(sheet)
+    }
+
+    /// Cells for a splash of type `slot` at `frame` of `total` frames
+    /// variant: any per-splash value, picks among the slot's sprites
+    pub fn frame(&self, slot: u8, variant: u32, frame: u8, total: u8) -> Option<&[SpriteCell]> {
+        let sprites = self.slots.get(slot as usize)?;
+        if sprites.is_empty() { return None; }
+
+        let (first, count) = self.sprites[sprites[variant as usize % sprites.len()] as usize];
+        let f = (frame as usize * count as usize / total.max(1) as usize).min(count as usize - 1);
+        let (start, n) = self.frames[first as usize + f];
+        Some(&self.cells[start as usize..start as usize + n as usize])
+    }
+
+    fn empty() -> Self {
+        Self {
+            cells: Vec::new(),
+            frames: Vec::new(),
+            sprites: Vec::new(),
+            slots: Default::default(),
+        }
+    }
+
+    fn begin_sprite(&mut self, slot: u8) {
+        self.slots[slot as usize].push(self.sprites.len() as u16);
+        self.sprites.push((self.frames.len() as u16, 0));
+    }
+
+    fn push_frame(&mut self, cells: &[SpriteCell]) {
+        self.frames.push((self.cells.len() as u16, cells.len() as u16));
+        self.cells.extend_from_slice(cells);
+        if let Some(sprite) = self.sprites.last_mut() {
+            sprite.1 += 1;
+        }
+    }
+}
+
+// Byte cursor with bounds checks
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], &'static str> {
+        let end = self.pos + n;
+        if end > self.bytes.len() { return Err("splash sheet truncated"); }
+        let out = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(out)
+    }
+
+    fn u8(&mut self) -> Result<u8, &'static str> {
+        Ok(self.take(1)?[0])
+    }
+}
+
+// Built-in sprites, 8 frames each (glyphs: 0 . 1 | 2 ' 4 \ 5 / 6 .)
+// splashes/default.txt is the same sheet as text; tests/splash_sheet.rs
+// keeps the two in step.
+
+static CROWN: [&[SpriteCell]; 8] = [
+    &[c(0, 0, 0, 0)],
+    &[c(-1, 1, 0, 4), c(0, 0, 0, 0), c(1, 1, 0, 5)],
+    &[c(0, 1, 1, 1), c(-1, 1, 0, 4), c(1, 1, 0, 5)],
+    &[c(-2, 1, 1, 4), c(0, 1, 1, 1), c(2, 1, 1, 5), c(-1, 0, 0, 4), c(1, 0, 0, 5)],
+    &[c(-2, 2, 2, 2), c(0, 1, 2, 2), c(2, 2, 2, 2), c(-2, 1, 1, 4), c(2, 1, 1, 5)],
+    &[c(-3, 2, 2, 2), c(0, 1, 2, 2), c(3, 2, 2, 2)],
+    &[c(-2, 1, 1, 2), c(2, 1, 1, 2)],
+    &[c(-1, 1, 0, 6), c(1, 1, 0, 6)],
+];
+
+static LEFT: [&[SpriteCell]; 8] = [
+    &[c(0, 0, 0, 0)],
+    &[c(-1, 1, 0, 4), c(0, 0, 0, 0)],
+    &[c(-1, 1, 1, 4), c(0, 1, 1, 1), c(-2, 1, 0, 4)],
+    &[c(-2, 1, 2, 2), c(-1, 1, 1, 4), c(0, 1, 1, 1), c(-3, 1, 0, 4)],
+    &[c(-3, 1, 2, 2), c(-1, 1, 2, 2), c(-2, 1, 1, 4), c(0, 1, 1, 1)],
+    &[c(-4, 1, 2, 2), c(-2, 1, 2, 2), c(-3, 1, 1, 4)],
+    &[c(-3, 1, 1, 2), c(-1, 1, 1, 2)],
+    &[c(-2, 1, 0, 6)],
+];
+
+static RIGHT: [&[SpriteCell]; 8] = [
+    &[c(0, 0, 0, 0)],
+    &[c(0, 0, 0, 0), c(1, 1, 0, 5)],
+    &[c(0, 1, 1, 1), c(1, 1, 1, 5), c(2, 1, 0, 5)],
+    &[c(0, 1, 1, 1), c(1, 1, 1, 5), c(2, 1, 2, 2), c(3, 1, 0, 5)],
+    &[c(0, 1, 1, 1), c(2, 1, 1, 5), c(1, 1, 2, 2), c(3, 1, 2, 2)],
+    &[c(3, 1, 1, 5), c(2, 1, 2, 2), c(4, 1, 2, 2)],
+    &[c(1, 1, 1, 2), c(3, 1, 1, 2)],
+    &[c(2, 1, 0, 6)],
+];
+
+static SPRAY: [&[SpriteCell]; 8] = [
+    &[c(0, 0, 0, 0)],
+    &[c(0, 1, 0, 0), c(-1, 1, 0, 2), c(1, 1, 0, 2)],
+    &[c(0, 2, 1, 2), c(-1, 1, 1, 2), c(2, 1, 0, 2)],
+    &[c(0, 2, 2, 2), c(-2, 1, 1, 2), c(1, 1, 1, 2), c(3, 1, 0, 2)],
+    &[c(-1, 1, 2, 2), c(2, 1, 2, 2), c(-2, 1, 1, 2), c(3, 1, 1, 2)],
+    &[c(-2, 1, 2, 2), c(1, 1, 2, 2), c(3, 1, 1, 2)],
+    &[c(-1, 1, 1, 2), c(2, 1, 1, 2)],
+    &[c(0, 1, 0, 6)],
+];
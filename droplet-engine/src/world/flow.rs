@@ -4,6 +4,7 @@
 // Water flows toward lower elevation (higher depth values).
 
 use crate::scene::{BG_WIDTH, BG_HEIGHT, BG_FLOW_X, BG_FLOW_Y};
+use super::sample::bilinear;
 
 /// Flow direction at pixel, returns (fx, fy) in range [-1.0, 1.0]
 /// Returns (0, 0) if no flow (flat or non-ground)
@@ -16,6 +17,15 @@ pub fn get_flow(x: usize, y: usize) -> (f32, f32) {
     (fx, fy)
 }
 
+/// Flow direction at continuous background position (bilinear)
+/// Avoids stair-stepping when streams move less than a pixel per tick
+#[inline(always)]
+pub fn get_flow_bilinear(x: f32, y: f32) -> (f32, f32) {
+    let fx = bilinear(&BG_FLOW_X, x, y) / 127.0;
+    let fy = bilinear(&BG_FLOW_Y, x, y) / 127.0;
+    (fx, fy)
+}
+
 /// Check if there's significant flow at this position
 /// Returns false for flat areas where water would pool
 #[inline(always)]
@@ -0,0 +1,86 @@
+// material.rs - Surface materials from semantic segmentation
+//
+// Maps ADE20K class IDs (BG_SEGMENTS) to a small set of materials.
+// Each material decides how impacts and streams behave on it.
+
+use crate::scene::{BG_WIDTH, BG_HEIGHT, BG_SEGMENTS};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Material {
+    Stone,
+    Water,
+    Grass,
+    Foliage,
+    Metal,
+    Glass,
+    Fabric,
+}
+
+/// How a splash on this material picks its shape
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SplashStyle {
+    Normal,  // biased by surface normal
+    Crown,   // symmetric crown (water)
+    Spray,   // scattered droplets (leaves, metal)
+    Muted,   // tiny puff, no wings (fabric, grass)
+}
+
+pub struct MaterialProps {
+    pub splash_chance: f32,  // probability an impact splashes
+    pub slide_chance: f32,   // probability an impact spawns a stream (needs flow)
+    pub absorption: f32,     // 0 = streams slide freely, 1 = soak in fast
+    pub style: SplashStyle,
+}
+
+// Indexed by Material as usize
+static MATERIALS: [MaterialProps; 7] = [
+    // Stone - baseline behaviour
+    MaterialProps { splash_chance: 1.0, slide_chance: 0.6, absorption: 0.0, style: SplashStyle::Normal },
+    // Water
+    MaterialProps { splash_chance: 0.9, slide_chance: 0.0, absorption: 1.0, style: SplashStyle::Crown },
+    // Grass
+    MaterialProps { splash_chance: 0.3, slide_chance: 0.1, absorption: 0.8, style: SplashStyle::Muted },
+    // Foliage
+    MaterialProps { splash_chance: 0.6, slide_chance: 0.3, absorption: 0.3, style: SplashStyle::Spray },
+    // Metal
+    MaterialProps { splash_chance: 1.0, slide_chance: 0.7, absorption: 0.0, style: SplashStyle::Spray },
+    // Glass
+    MaterialProps { splash_chance: 0.8, slide_chance: 0.9, absorption: 0.0, style: SplashStyle::Normal },
+    // Fabric
+    MaterialProps { splash_chance: 0.2, slide_chance: 0.2, absorption: 0.6, style: SplashStyle::Muted },
+];
+
+impl Material {
+    #[inline(always)]
+    pub fn props(self) -> &'static MaterialProps {
+        &MATERIALS[self as usize]
+    }
+
+    /// Material for an ADE20K class ID (unlisted classes are stone)
+    pub const fn from_class(class: u8) -> Material {
+        match class {
+            21 | 26 | 60 | 104 | 109 | 113 | 128 => Material::Water,  // water, sea, river, fountain, pool, waterfall, lake
+            9 | 13 | 29 | 46 | 68 | 91 | 94 => Material::Grass,       // grass, earth, field, sand, hill, dirt track, land
+            4 | 17 | 66 | 72 => Material::Foliage,                    // tree, plant, flower, palm
+            20 | 38 | 76 | 80 | 83 | 87 | 93 | 95 | 102 | 103
+                | 116 | 127 | 136 => Material::Metal,                 // vehicles, railings, poles, lights
+            8 | 27 | 58 | 130 | 141 | 147 => Material::Glass,        // windowpane, mirror, screens, glass
+            18 | 23 | 28 | 39 | 86 | 106 | 114 | 131 | 149 => Material::Fabric,  // curtain, sofa, rug, awning, canopy, tent, flag
+            _ => Material::Stone,
+        }
+    }
+}
+
+/// Material at pixel
+#[inline(always)]
+pub fn get_material(x: usize, y: usize) -> Material {
+    if x >= BG_WIDTH || y >= BG_HEIGHT { return Material::Stone; }
+    Material::from_class(BG_SEGMENTS[y][x])
+}
+
+/// ADE20K class ID at pixel (None off the scene)
+#[inline(always)]
+pub fn get_segment(x: usize, y: usize) -> Option<u8> {
+    if x >= BG_WIDTH || y >= BG_HEIGHT { return None; }
+    Some(BG_SEGMENTS[y][x])
+}
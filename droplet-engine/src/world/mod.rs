@@ -1,10 +1,16 @@
 // world/ - Terrain queries
 //
 // Pure functions to query scene geometry.
-// No state, no allocation - just math.
+// No state, no allocation - just math. The one exception is DepthPyramid,
+// built once from scene data and read-only afterwards.
 
 mod terrain;
 mod flow;
+mod material;
+mod pyramid;
+mod sample;
 
 pub use terrain::*;
 pub use flow::*;
+pub use material::*;
+pub use pyramid::*;
@@ -0,0 +1,108 @@
+// pyramid.rs - Min/max depth pyramid for swept collision
+//
+// Each level halves the row resolution of BG_DEPTH per column, storing the
+// min and max depth of the rows it covers. A sweep skips whole blocks whose
+// depth range can't match the drop, and only tests single pixels near a hit.
+//
+// Built once from scene data; queries are pure.
+
+use crate::scene::{BG_WIDTH, BG_HEIGHT, BG_DEPTH};
+use super::{hits_surface, SKY_DEPTH};
+
+const LEVELS: usize = 5;  // coarsest block = 32 rows
+
+pub struct DepthPyramid {
+    // levels[l] covers blocks of 2^(l+1) rows, row-major (block, x)
+    min: [Vec<u8>; LEVELS],
+    max: [Vec<u8>; LEVELS],
+}
+
+impl DepthPyramid {
+    pub fn new() -> Self {
+        let mut min: [Vec<u8>; LEVELS] = Default::default();
+        let mut max: [Vec<u8>; LEVELS] = Default::default();
+
+        for l in 0..LEVELS {
+            let rows = BG_HEIGHT.div_ceil(2 << l);
+            min[l] = vec![u8::MAX; rows * BG_WIDTH];
+            max[l] = vec![0; rows * BG_WIDTH];
+
+            for by in 0..rows {
+                for x in 0..BG_WIDTH {
+                    let (lo, hi) = if l == 0 {
+                        // From the depth map itself
+                        let y0 = by * 2;
+                        let y1 = (y0 + 1).min(BG_HEIGHT - 1);
+                        let (a, b) = (BG_DEPTH[y0][x], BG_DEPTH[y1][x]);
+                        (a.min(b), a.max(b))
+                    } else {
+                        // From the two blocks of the finer level
+                        let below = BG_HEIGHT.div_ceil(1 << l);
+                        let c0 = by * 2;
+                        let c1 = (c0 + 1).min(below - 1);
+                        let (fmin, fmax) = (&min[l - 1], &max[l - 1]);
+                        (
+                            fmin[c0 * BG_WIDTH + x].min(fmin[c1 * BG_WIDTH + x]),
+                            fmax[c0 * BG_WIDTH + x].max(fmax[c1 * BG_WIDTH + x]),
+                        )
+                    };
+                    min[l][by * BG_WIDTH + x] = lo;
+                    max[l][by * BG_WIDTH + x] = hi;
+                }
+            }
+        }
+
+        Self { min, max }
+    }
+
+    /// First row in [y0, y1] where a drop at depth z hits a surface in column x
+    pub fn sweep(&self, x: usize, y0: usize, y1: usize, drop_z: f32, margin: u8) -> Option<usize> {
+        if x >= BG_WIDTH || y0 > y1 { return None; }
+        let y1 = y1.min(BG_HEIGHT - 1);
+
+        let drop_depth = ((1.0 - drop_z) * 255.0) as u8 as i16;
+        let margin = margin as i16;
+
+        let mut y = y0;
+        let mut cap = LEVELS;  // max level to try at y (lowered after a maybe-hit)
+        while y <= y1 {
+            // Largest aligned block that fits in the remaining span
+            let mut l = cap;
+            while l > 0 {
+                let size = 1 << l;
+                if y.is_multiple_of(size) && y + size - 1 <= y1 { break; }
+                l -= 1;
+            }
+
+            if l == 0 {
+                if hits_surface(x, y, drop_z, margin as u8) { return Some(y); }
+                y += 1;
+                cap = LEVELS;
+                continue;
+            }
+
+            // Can anything in this block match the drop?
+            let idx = (y >> l) * BG_WIDTH + x;
+            let (lo, hi) = (self.min[l - 1][idx] as i16, self.max[l - 1][idx] as i16);
+            let could_hit = hi > SKY_DEPTH as i16
+                && drop_depth > lo - margin
+                && drop_depth < hi + margin;
+
+            if could_hit {
+                // Look closer at the same rows
+                cap = l - 1;
+            } else {
+                y += 1 << l;
+                cap = LEVELS;
+            }
+        }
+
+        None
+    }
+}
+
+impl Default for DepthPyramid {
+    fn default() -> Self {
+        Self::new()
+    }
+}
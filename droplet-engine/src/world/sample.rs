@@ -0,0 +1,21 @@
+// sample.rs - Bilinear sampling of scene grids
+//
+// Background coordinates are continuous: pixel (x, y) covers [x, x+1) and
+// its value sits at the center (x + 0.5, y + 0.5). Edges clamp.
+
+use crate::scene::{BG_WIDTH, BG_HEIGHT};
+
+/// Bilinear sample of a BG_WIDTH x BG_HEIGHT grid at (x, y)
+#[inline(always)]
+pub(super) fn bilinear<T: Copy + Into<f32>>(grid: &[[T; BG_WIDTH]; BG_HEIGHT], x: f32, y: f32) -> f32 {
+    let u = (x - 0.5).clamp(0.0, (BG_WIDTH - 1) as f32);
+    let v = (y - 0.5).clamp(0.0, (BG_HEIGHT - 1) as f32);
+
+    let (x0, y0) = (u as usize, v as usize);
+    let (x1, y1) = ((x0 + 1).min(BG_WIDTH - 1), (y0 + 1).min(BG_HEIGHT - 1));
+    let (tx, ty) = (u - x0 as f32, v - y0 as f32);
+
+    let top = grid[y0][x0].into() * (1.0 - tx) + grid[y0][x1].into() * tx;
+    let bot = grid[y1][x0].into() * (1.0 - tx) + grid[y1][x1].into() * tx;
+    top * (1.0 - ty) + bot * ty
+}
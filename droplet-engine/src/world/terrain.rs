@@ -3,7 +3,12 @@
 // All coordinates are in background space (BG_WIDTH x BG_HEIGHT).
 // Caller must convert from screen space if needed.
 
-use crate::scene::{BG_WIDTH, BG_HEIGHT, BG_DEPTH, BG_GROUND, BG_NORMAL_X, BG_NORMAL_Y};
+use crate::scene::{BG_WIDTH, BG_HEIGHT, BG_DEPTH, BG_GROUND, BG_NORMAL_X, BG_NORMAL_Y, BG_SHELTER};
+
+use super::sample::bilinear;
+
+/// Depth at or below this is sky - nothing to hit
+pub const SKY_DEPTH: u8 = 30;
 
 /// Depth at pixel (0.0 = far, 1.0 = near)
 #[inline]
@@ -12,6 +17,12 @@ pub fn get_depth(x: usize, y: usize) -> f32 {
     BG_DEPTH[y][x] as f32 / 255.0
 }
 
+/// Depth at continuous background position (bilinear)
+#[inline]
+pub fn get_depth_bilinear(x: f32, y: f32) -> f32 {
+    bilinear(&BG_DEPTH, x, y) / 255.0
+}
+
 /// Height at pixel (0.0 = low, 1.0 = high)
 /// Inverse of depth - near objects are "lower" in world space
 #[inline]
@@ -42,7 +53,7 @@ pub fn hits_surface(x: usize, y: usize, drop_z: f32, margin: u8) -> bool {
     let bg_depth = BG_DEPTH[y][x];
 
     // Skip sky (depth near 0)
-    if bg_depth <= 30 { return false; }
+    if bg_depth <= SKY_DEPTH { return false; }
 
     // Check depth match
     let drop_depth = ((1.0 - drop_z) * 255.0) as u8;
@@ -50,6 +61,20 @@ pub fn hits_surface(x: usize, y: usize, drop_z: f32, margin: u8) -> bool {
     diff < margin
 }
 
+/// Check if drop at depth z is under cover at (x, y)
+/// Covered when the roof above (BG_SHELTER) is nearer than the drop,
+/// but by less than `reach` - how far back the roof extends from its edge.
+#[inline(always)]
+pub fn is_sheltered(x: usize, y: usize, drop_z: f32, reach: u8) -> bool {
+    if x >= BG_WIDTH || y >= BG_HEIGHT { return false; }
+
+    let roof = BG_SHELTER[y][x];
+    if roof == 0 { return false; }
+
+    let drop_depth = ((1.0 - drop_z) * 255.0) as u8;
+    drop_depth < roof && roof - drop_depth < reach
+}
+
 /// Surface normal at pixel (returns x,y components, z assumed positive/up)
 /// Normal points outward from surface. Values normalized to [-1, 1].
 #[inline(always)]
@@ -59,3 +84,12 @@ pub fn get_normal(x: usize, y: usize) -> (f32, f32) {
     let ny = BG_NORMAL_Y[y][x] as f32 / 127.0;
     (nx, ny)
 }
+
+/// Surface normal at continuous background position (bilinear)
+/// Smooth across pixel boundaries - use when the screen is finer than the scene.
+#[inline(always)]
+pub fn get_normal_bilinear(x: f32, y: f32) -> (f32, f32) {
+    let nx = bilinear(&BG_NORMAL_X, x, y) / 127.0;
+    let ny = bilinear(&BG_NORMAL_Y, x, y) / 127.0;
+    (nx, ny)
+}
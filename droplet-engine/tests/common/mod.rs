@@ -0,0 +1,30 @@
+// Worlds shared by the integration tests (each test file uses some of them)
+#![allow(dead_code)]
+
+use droplet_engine::sim::{Precipitation, RainWorld, SplashMode, WeatherState};
+
+/// A seeded storm with ballistic splashes, not yet ticked
+pub fn storm() -> RainWorld {
+    let mut world = RainWorld::new(160, 48);
+    world.set_seed(3);
+    world.set_weather(WeatherState::Storm, 0.0);
+    world.set_splash_mode(SplashMode::Ballistic);
+    world
+}
+
+/// A busy world: storm, wind, ballistic splashes, a spell of snow and hail
+pub fn stormy_world() -> RainWorld {
+    let mut world = RainWorld::new(160, 48);
+    world.set_seed(42);
+    world.set_wind(12.0);
+    world.set_weather(WeatherState::Storm, 0.0);
+    world.set_splash_mode(SplashMode::Ballistic);
+    for kind in [Precipitation::Snow, Precipitation::Hail, Precipitation::Rain] {
+        world.set_precipitation(kind);
+        for _ in 0..200 {
+            world.tick();
+        }
+    }
+    world.tick_dt(0.01);
+    world
+}
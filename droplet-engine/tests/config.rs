@@ -0,0 +1,73 @@
+// Config JSON: every preset round-trips, and anything out of range, unknown
+// or malformed is rejected without touching the world's config.
+
+use droplet_engine::sim::{ConfigPreset, RainWorld, SimConfig};
+
+#[test]
+fn presets_round_trip_through_json() {
+    for v in 0..=4 {
+        let preset = ConfigPreset::from_u8(v).unwrap();
+        let config = SimConfig::preset(preset);
+        assert_eq!(SimConfig::from_json(&config.to_json()), Ok(config), "{preset:?}");
+
+        // And through a world
+        let mut world = RainWorld::new(40, 20);
+        world.load_config_json(&config.to_json()).unwrap();
+        assert_eq!(*world.config(), config);
+    }
+
+    // Missing keys keep their defaults, whitespace is fine
+    assert_eq!(SimConfig::from_json("{}"), Ok(SimConfig::DEFAULT));
+    let config = SimConfig::from_json(" {\n  \"splash_time\" : 1.5 } \n").unwrap();
+    assert_eq!(config, SimConfig { splash_time: 1.5, ..SimConfig::DEFAULT });
+}
+
+#[test]
+fn out_of_range_and_unknown_keys_are_rejected() {
+    for name in SimConfig::names() {
+        let (min, max) = SimConfig::range(name).unwrap();
+        for ok in [min, max] {
+            assert!(SimConfig::from_json(&format!("{{\"{name}\": {ok}}}")).is_ok(), "{name} = {ok}");
+        }
+        // 1e39 overflows f32 to infinity
+        for bad in [(min - 1.0).to_string(), (max + 1.0).to_string(), "1e39".into()] {
+            let json = format!("{{\"{name}\": {bad}}}");
+            assert_eq!(SimConfig::from_json(&json), Err("config value out of range"), "{json}");
+        }
+    }
+
+    assert_eq!(SimConfig::from_json("{\"vel_nearer\": 100}"), Err("unknown config parameter"));
+    assert_eq!(SimConfig::from_json("{\"vel_near\": 100, \"\": 1}"), Err("unknown config parameter"));
+
+    // A rejected object leaves the world's config alone, even its valid keys
+    let mut world = RainWorld::new(40, 20);
+    world.apply_config_preset(ConfigPreset::Soaked);
+    assert!(world.load_config_json("{\"splash_time\": 1.5, \"wetness\": 1}").is_err());
+    assert_eq!(*world.config(), SimConfig::preset(ConfigPreset::Soaked));
+}
+
+#[test]
+fn malformed_json_is_rejected() {
+    for json in [
+        "",
+        "{",
+        "[]",
+        "{\"vel_near\": 100,}",
+        "{\"vel_near\": 100 \"vel_far\": 20}",
+        "{\"vel_near\" 100}",
+        "{vel_near: 100}",
+        "{\"vel_near\": 100}}",
+        "{\"vel_near\": 100} x",
+        "{\"vel_near\": NaN}",
+        "{\"vel_near\": Infinity}",
+        "{\"vel_near\": -}",
+        "{\"vel_near\": \"100\"}",
+        "{\"vel_near\": null}",
+        "{\"vel_near\": {\"value\": 100}}",
+        "{\"vel_near\": [100]}",
+        "{\"vel\\u005fnear\": 100}",
+        "{\"vel_near",
+    ] {
+        assert!(SimConfig::from_json(json).is_err(), "{json:?}");
+    }
+}
@@ -0,0 +1,80 @@
+// Impact events: the queue matches the stats, honours cap and sampling, and
+// never disturbs the simulation.
+
+mod common;
+
+use common::storm;
+use droplet_engine::sim::{Cause, Impact, CAUSES};
+
+#[test]
+fn events_match_removal_causes() {
+    let mut world = storm();
+    world.set_impact_events(1 << 16, 1.0);
+
+    // Single steps and multi-step frames alike
+    let mut kinds = [0; CAUSES];
+    for i in 0..300 {
+        if i % 2 == 0 { world.tick() } else { world.tick_dt(0.05) }
+        let s = world.stats();
+        assert_eq!(world.impact_overflow(), 0);
+
+        let mut counts = [0; CAUSES];
+        for e in world.impacts() {
+            counts[e.kind as usize] += 1;
+            assert!((0.0..=1.0).contains(&e.z));
+            assert!(e.nx.is_finite() && e.ny.is_finite());
+        }
+        // Other also counts removals that aren't impacts (faded, off screen...)
+        let other = Cause::Other as usize;
+        assert_eq!(counts[..other], s.causes[..other]);
+        assert!(counts[other] <= s.causes[other]);
+        for (k, n) in kinds.iter_mut().zip(counts) {
+            *k += n;
+        }
+    }
+    assert!(kinds[Cause::Ground as usize] + kinds[Cause::Surface as usize] > 0);
+    assert!(kinds[Cause::Pool as usize] + kinds[Cause::Other as usize] > 0);
+    assert_eq!(std::mem::size_of::<Impact>(), 24);
+}
+
+#[test]
+fn cap_and_sampling() {
+    let mut world = storm();
+    world.tick();
+    assert!(world.impacts().is_empty());
+
+    world.set_impact_events(2, 1.0);
+    let mut overflow = 0;
+    for _ in 0..120 {
+        world.tick();
+        assert!(world.impacts().len() <= 2);
+        overflow += world.impact_overflow();
+    }
+    assert!(overflow > 0);
+
+    world.set_impact_events(100, 0.0);
+    for _ in 0..60 {
+        world.tick();
+        assert!(world.impacts().is_empty());
+    }
+}
+
+#[test]
+fn sampling_leaves_the_rain_alone() {
+    let mut plain = storm();
+    let mut sampled = storm();
+    sampled.set_impact_events(64, 0.5);
+
+    for _ in 0..200 {
+        plain.tick();
+        sampled.tick();
+        assert_eq!(plain.output(), sampled.output());
+    }
+
+    let mut again = storm();
+    again.set_impact_events(64, 0.5);
+    for _ in 0..200 {
+        again.tick();
+    }
+    assert_eq!(again.impacts(), sampled.impacts());
+}
@@ -0,0 +1,116 @@
+// Recording and replay: a recorded session re-runs to the same state, and a
+// run that renders differently is caught at the frame it starts to.
+
+use droplet_engine::render::Encoder;
+use droplet_engine::sim::{replay, Precipitation, RainWorld, RenderContext, System, StepContext, ViewportMode, WeatherState};
+
+// A session with a bit of everything the front-end does
+fn record_session(checksums: bool) -> (RainWorld, Vec<u8>) {
+    let mut world = RainWorld::new(120, 40);
+    world.set_seed(7);
+    for _ in 0..30 {
+        world.tick();
+    }
+
+    world.start_recording(checksums);
+    world.set_weather(WeatherState::Downpour, 1.0);
+    for i in 0..120 {
+        match i {
+            20 => world.resize(100, 50),
+            40 => world.set_viewport(ViewportMode::Contain, 0.5, 1.0),
+            60 => world.set_config_param("splash_time", 0.8).unwrap(),
+            80 => world.set_precipitation(Precipitation::Hail),
+            100 => world.set_wind(-20.0),
+            _ => {}
+        }
+        world.tick_dt(0.007 + i as f32 * 0.0003);
+    }
+    let log = world.stop_recording().unwrap();
+    (world, log)
+}
+
+#[test]
+fn replay_reproduces_the_session() {
+    let (world, log) = record_session(true);
+
+    let mut replayed = RainWorld::new(10, 10);
+    let report = replay(&mut replayed, &log).unwrap();
+    assert_eq!(report.frames, 120);
+    assert!(report.checksums);
+    assert_eq!(report.first_divergence, None);
+    assert_eq!(replayed.snapshot(), world.snapshot());
+}
+
+#[test]
+fn replay_without_checksums_still_runs() {
+    let (world, log) = record_session(false);
+
+    let mut replayed = RainWorld::new(10, 10);
+    let report = replay(&mut replayed, &log).unwrap();
+    assert_eq!((report.frames, report.checksums), (120, false));
+    assert_eq!(replayed.output(), world.output());
+}
+
+// Draws a marker from its 50th step on
+struct Glitch(u32);
+
+impl System for Glitch {
+    fn name(&self) -> &'static str { "glitch" }
+
+    fn update(&mut self, _ctx: &mut StepContext) {
+        self.0 += 1;
+    }
+
+    fn render(&self, _ctx: &RenderContext, encoder: &mut Encoder) {
+        if self.0 >= 50 {
+            encoder.plot_raw(0, 0, 255);
+        }
+    }
+}
+
+#[test]
+fn replay_reports_the_first_divergent_frame() {
+    let mut world = RainWorld::new(80, 24);
+    world.start_recording(true);
+    for _ in 0..100 {
+        world.tick();
+    }
+    let log = world.stop_recording().unwrap();
+
+    let mut replayed = RainWorld::new(80, 24);
+    replayed.add_system(2000, Box::new(Glitch(0))).unwrap();
+    let report = replay(&mut replayed, &log).unwrap();
+    assert_eq!(report.first_divergence, Some(49));
+    assert_eq!(report.frames, 50);
+}
+
+#[test]
+fn invalid_recordings_are_rejected() {
+    let (_, log) = record_session(true);
+    let mut world = RainWorld::new(10, 10);
+
+    assert!(replay(&mut world, &log[..3]).is_err());
+    assert!(replay(&mut world, &log[..40]).is_err());
+    let snapshot = world.snapshot();
+    assert!(replay(&mut world, &snapshot).is_err());
+}
+
+#[test]
+fn impossible_resizes_are_rejected() {
+    let mut world = RainWorld::new(40, 20);
+    world.start_recording(false);
+    world.resize(30, 10);
+    let log = world.stop_recording().unwrap();
+
+    // The resize is the last event: tag, then width and height
+    let size = log.len() - 8;
+    assert_eq!(log[size..], [30, 0, 0, 0, 10, 0, 0, 0]);
+    assert!(replay(&mut RainWorld::new(40, 20), &log).is_ok());
+
+    for (w, h) in [(0, 10), (30, 0), (u32::MAX, 2), (1 << 13, 1 << 13)] {
+        let mut bad = log.clone();
+        bad[size..size + 4].copy_from_slice(&u32::to_le_bytes(w));
+        bad[size + 4..].copy_from_slice(&u32::to_le_bytes(h));
+        assert_eq!(replay(&mut RainWorld::new(40, 20), &bad).err(), Some("recording is corrupt"), "{w} x {h}");
+    }
+}
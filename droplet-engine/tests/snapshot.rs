@@ -0,0 +1,107 @@
+// Snapshot round trips: a restored world must carry on exactly like the one
+// that was saved.
+
+mod common;
+
+use common::stormy_world;
+use droplet_engine::sim::{Entities, RainWorld, SimConfig, SnapReader, SnapWriter, StepContext, System};
+
+#[test]
+fn restored_world_continues_identically() {
+    let mut a = stormy_world();
+    let blob = a.snapshot();
+
+    let mut b = RainWorld::new(80, 24);
+    b.restore(&blob).unwrap();
+    assert_eq!((b.width(), b.height()), (160, 48));
+    assert_eq!(b.snapshot(), blob);
+    assert_eq!(b.output(), a.output());
+
+    for _ in 0..300 {
+        a.tick_dt(0.013);
+        b.tick_dt(0.013);
+    }
+    assert_eq!(b.snapshot(), a.snapshot());
+    assert_eq!(b.output(), a.output());
+}
+
+#[test]
+fn bad_snapshots_leave_the_world_alone() {
+    let blob = stormy_world().snapshot();
+
+    let mut world = RainWorld::new(80, 24);
+    world.tick();
+    let before = world.snapshot();
+
+    let mut bad_magic = blob.clone();
+    bad_magic[0] = b'X';
+    let mut bad_version = blob.clone();
+    bad_version[4] ^= 0xFF;
+    let mut trailing = blob.clone();
+    trailing.push(0);
+
+    // Screen size right after magic, version and scene size
+    let mut huge = blob.clone();
+    huge[10..18].copy_from_slice(&[100_000u32.to_le_bytes(), 100_000u32.to_le_bytes()].concat());
+    let mut empty = blob.clone();
+    empty[10..14].copy_from_slice(&0u32.to_le_bytes());
+
+    // The weather's current spawn rate, after the header and screen, the
+    // config, three mode bytes, five viewport and timing floats, the wind,
+    // and the weather's blend origin, target and tick counters
+    let at = 22 + SimConfig::names().count() * 4 + 3 + 20 + 32 + 24 + 9;
+    assert_eq!(blob[at..at + 4], 3.0f32.to_le_bytes());
+    let mut flood = blob.clone();
+    flood[at..at + 4].copy_from_slice(&1e30f32.to_le_bytes());
+    let mut nan = blob.clone();
+    nan[at..at + 4].copy_from_slice(&f32::NAN.to_le_bytes());
+
+    for bytes in [&blob[..blob.len() / 2], &blob[..3], &bad_magic, &bad_version, &trailing, &huge, &empty, &flood, &nan] {
+        assert!(world.restore(bytes).is_err());
+        assert_eq!(world.snapshot(), before);
+    }
+}
+
+// A custom system keeping a counter in its own pool
+struct Counter(u32);
+
+struct Ticker;
+
+impl System for Ticker {
+    fn name(&self) -> &'static str { "ticker" }
+
+    fn update(&mut self, ctx: &mut StepContext) {
+        if let Some(c) = ctx.entities.get_mut::<Counter>() {
+            c.0 += 1;
+        }
+    }
+
+    fn save(&self, entities: &Entities, w: &mut SnapWriter) {
+        w.u32(entities.get::<Counter>().map_or(0, |c| c.0));
+    }
+
+    fn load(&mut self, entities: &mut Entities, r: &mut SnapReader) -> Result<(), &'static str> {
+        entities.insert(Counter(r.u32()?));
+        Ok(())
+    }
+}
+
+#[test]
+fn custom_systems_save_their_own_state() {
+    let mut a = RainWorld::new(80, 24);
+    a.add_system(650, Box::new(Ticker)).unwrap();
+    a.entities_mut().insert(Counter(0));
+    for _ in 0..10 {
+        a.tick();
+    }
+
+    let mut b = RainWorld::new(80, 24);
+    b.add_system(650, Box::new(Ticker)).unwrap();
+    b.restore(&a.snapshot()).unwrap();
+    assert_eq!(b.entities().get::<Counter>().map(|c| c.0), Some(10));
+
+    // A world without the system skips its section
+    let mut c = RainWorld::new(80, 24);
+    c.restore(&a.snapshot()).unwrap();
+    assert!(c.entities().get::<Counter>().is_none());
+}
@@ -0,0 +1,37 @@
+// Splash sheets: the built-in sprites and splashes/default.txt describe the
+// same patterns, so editing one without the other fails here.
+
+use droplet_engine::sim::{Kind, RainWorld, WeatherState};
+use std::process::Command;
+
+fn compile_default_sheet() -> Vec<u8> {
+    let src = concat!(env!("CARGO_MANIFEST_DIR"), "/splashes/default.txt");
+    let out = std::env::temp_dir().join(format!("droplet-default-{}.bin", std::process::id()));
+    let status = Command::new(env!("CARGO_BIN_EXE_splashsheet")).arg(src).arg(&out).output().unwrap().status;
+    assert!(status.success());
+    let bytes = std::fs::read(&out).unwrap();
+    let _ = std::fs::remove_file(&out);
+    bytes
+}
+
+#[test]
+fn builtin_sprites_match_default_txt() {
+    let sheet = compile_default_sheet();
+
+    let mut builtin = RainWorld::new(160, 48);
+    let mut loaded = RainWorld::new(160, 48);
+    assert!(loaded.load_splash_sheet(&sheet).is_ok());
+
+    let mut splashes = 0;
+    for world in [&mut builtin, &mut loaded] {
+        world.set_seed(5);
+        world.set_weather(WeatherState::Storm, 0.0);
+    }
+    for _ in 0..300 {
+        builtin.tick();
+        loaded.tick();
+        assert_eq!(builtin.output(), loaded.output());
+        splashes += builtin.stats().spawned[Kind::Splash as usize];
+    }
+    assert!(splashes > 0);
+}
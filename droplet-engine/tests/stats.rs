@@ -0,0 +1,92 @@
+// Stats: per-frame counts stay consistent with the pools they describe.
+
+mod common;
+
+use common::storm;
+use droplet_engine::sim::{Cause, Kind, PoolSizes, Stats, CAUSES, KINDS};
+
+#[test]
+fn counts_add_up_every_frame() {
+    let mut world = storm();
+    let mut live = [0; KINDS];
+
+    for _ in 0..300 {
+        world.tick();
+        let s = world.stats();
+
+        let expected: Vec<u32> = (0..KINDS).map(|k| live[k] + s.spawned[k] - s.died[k]).collect();
+        assert_eq!(s.live.to_vec(), expected);
+        live = s.live;
+
+        let died: u32 = s.died.iter().sum();
+        assert_eq!(s.causes.iter().sum::<u32>(), died);
+
+        let drop_hits = s.causes[Cause::Ground as usize] + s.causes[Cause::Surface as usize];
+        assert!(s.impacts.iter().sum::<u32>() <= drop_hits);
+        assert!(drop_hits <= s.died[Kind::Drop as usize]);
+    }
+
+    assert_eq!(live[Kind::Drop as usize] as usize, world.entities().drops.n);
+    assert!(live[Kind::Drop as usize] > 0);
+}
+
+#[test]
+fn counts_cover_every_step_of_a_frame() {
+    let mut world = storm();
+    for _ in 0..120 {
+        world.tick();
+    }
+
+    // 0.05 s runs three fixed steps; their counts add up
+    let before = world.stats().live;
+    world.tick_dt(0.05);
+    let s = world.stats();
+    let expected: Vec<u32> = (0..KINDS).map(|k| before[k] + s.spawned[k] - s.died[k]).collect();
+    assert_eq!(s.live.to_vec(), expected);
+    assert!(s.spawned[Kind::Drop as usize] > 0);
+
+    // A frame with no step reports that nothing happened
+    world.tick_dt(0.0);
+    let s = world.stats();
+    assert_eq!(s.spawned, [0; KINDS]);
+    assert_eq!(s.died, [0; KINDS]);
+    assert_eq!(s.causes, [0; CAUSES]);
+}
+
+#[test]
+fn refused_spawns_are_counted() {
+    let mut world = storm();
+    world.set_capacity(PoolSizes { drops: 10, splashes: 10, streams: 10 });
+
+    let mut refused = 0;
+    for _ in 0..60 {
+        world.tick();
+        refused += world.stats().refused[Kind::Drop as usize];
+    }
+    assert_eq!(refused, world.dropped_spawns()[0]);
+    assert!(refused > 0);
+}
+
+#[test]
+fn flat_layout_and_profiling() {
+    let mut world = storm();
+    world.tick();
+    assert!(world.stats().phases.is_empty());
+
+    world.set_profiling(true);
+    world.tick_dt(0.05);
+    let s = world.stats();
+    let names: Vec<_> = world.phase_names().collect();
+    assert_eq!(names.first(), Some(&"environment"));
+    assert_eq!(names.last(), Some(&"render"));
+    assert_eq!(s.phases.iter().map(|p| p.0).collect::<Vec<_>>(), names);
+    assert!(s.phases.iter().all(|p| p.1 >= 0.0));
+
+    let flat = s.flatten();
+    assert_eq!(flat.len(), Stats::PHASES + names.len());
+    assert_eq!(flat[Stats::LIVE], s.live[0] as f32);
+    assert_eq!(flat[Stats::CAUSES + CAUSES - 1], s.causes[Cause::Other as usize] as f32);
+
+    world.clear();
+    assert_eq!(world.entities().drops.n, 0);
+}
@@ -0,0 +1,76 @@
+// Swept collision: the depth pyramid finds the same first hit as testing
+// every row, and the rows a moving drop sweeps include where it ends up.
+
+use droplet_engine::sim::{SimConfig, Viewport};
+use droplet_engine::world::{get_depth_raw, hits_surface, DepthPyramid};
+
+// Past the scene on both axes, so out-of-range queries are covered too
+const COLS: usize = 400;
+const ROWS: usize = 200;
+
+fn brute_force(x: usize, y0: usize, y1: usize, z: f32, margin: u8) -> Option<usize> {
+    (y0..=y1).find(|&y| hits_surface(x, y, z, margin))
+}
+
+#[test]
+fn sweep_matches_brute_force() {
+    let pyramid = DepthPyramid::new();
+    let margin = SimConfig::default().depth_margin;
+
+    for x in (0..COLS).step_by(3) {
+        for z in [0.0, 0.3, 0.55, 0.8, 1.0] {
+            for y0 in (0..ROWS).step_by(3) {
+                for len in [0, 1, 2, 5, 17, 40, 200] {
+                    let y1 = y0 + len;
+                    assert_eq!(
+                        pyramid.sweep(x, y0, y1, z, margin),
+                        brute_force(x, y0, y1, z, margin),
+                        "x {x} rows {y0}..={y1} z {z}",
+                    );
+                }
+            }
+        }
+        // Empty span
+        assert_eq!(pyramid.sweep(x, 10, 9, 0.5, margin), None);
+    }
+}
+
+#[test]
+fn sweep_includes_the_new_row() {
+    let pyramid = DepthPyramid::new();
+    let margin = SimConfig::default().depth_margin;
+    // Two screen cells per background pixel each way
+    let vp = Viewport::new(640, 360);
+
+    let mut tested = 0;
+    for bx in (1..COLS).step_by(7) {
+        for by in (1..ROWS).step_by(5) {
+            let z = 1.0 - get_depth_raw(bx, by) as f32 / 255.0;
+            if !hits_surface(bx, by, z, margin) { continue; }
+            tested += 1;
+
+            let x = vp.screen_x(bx as f32 + 0.5);
+            let top = vp.screen_y(by as f32);
+
+            // Stayed in its row (and column)
+            let (y_prev, y) = (top + 0.2, top + 0.8);
+            let from = vp.sweep_from(x, y_prev, x, y);
+            let hit = pyramid.sweep(vp.col(x), from, vp.row(y), z, margin);
+            assert_eq!(hit, Some(by), "same row at {bx}, {by}");
+
+            // Blown in from the next column over, same row
+            let x_prev = vp.screen_x(bx as f32 - 0.5);
+            let from = vp.sweep_from(x_prev, y_prev, x, y);
+            let hit = pyramid.sweep(vp.col(x), from, vp.row(y), z, margin);
+            assert_eq!(hit, Some(by), "new column at {bx}, {by}");
+
+            // Blown in from the next column over, a row up: the old row
+            // was never tested in this column either
+            let y_prev = vp.screen_y(by as f32 - 0.5);
+            let from = vp.sweep_from(x_prev, y_prev, x, y);
+            let hit = pyramid.sweep(vp.col(x), from, vp.row(y), z, margin);
+            assert_eq!(hit, brute_force(bx, by - 1, by, z, margin), "row above at {bx}, {by}");
+        }
+    }
+    assert!(tested > 50);
+}
@@ -1,15 +1,87 @@
 type RainWorldInstance = {
     tick(): void;
+    tick_dt(seconds: number): void;
     output_ptr(): number;
     output_len(): number;
     width(): number;
     height(): number;
     resize(width: number, height: number): void;
+    set_reset_on_resize(reset: boolean): void;
+    reset_on_resize(): boolean;
+    set_viewport(mode: number, anchorX: number, anchorY: number): void;
+    viewport_mode(): number;
+    set_cell_aspect(aspect: number): void;
+    viewport_rect(): Float32Array;
     clear(): void;
     droplet_count(): number;
+    set_wind(strength: number): void;
+    set_wind_depth_drift(drift: number): void;
+    wind(): number;
+    set_weather(state: number, seconds: number): void;
+    set_weather_auto(auto: boolean): void;
+    weather(): number;
+    weather_intensity(): number;
+    set_precipitation(kind: number): void;
+    precipitation(): number;
+    lightning_flash(): number;
+    set_splash_mode(mode: number): void;
+    splash_mode(): number;
+    load_splash_sheet(bytes: Uint8Array): boolean;
+    reset_splash_sheet(): void;
+    set_seed(seed: number): void;
+    seed(): number;
+    snapshot(): Uint8Array;
+    restore(bytes: Uint8Array): boolean;
+    start_recording(checksums: boolean): void;
+    stop_recording(): Uint8Array;
+    is_recording(): boolean;
+    replay(bytes: Uint8Array): number;
+    stats(): Float32Array;
+    stats_phase_names(): string[];
+    set_profiling(enabled: boolean): void;
+    set_impact_events(cap: number, sampleRate: number): void;
+    impact_ptr(): number;
+    impact_count(): number;
+    impact_overflow(): number;
+    set_config_param(name: string, value: number): boolean;
+    config_param(name: string): number;
+    config_param_names(): string[];
+    config_param_range(name: string): Float32Array;
+    config_json(): string;
+    load_config_json(json: string): boolean;
+    apply_config_preset(preset: number): void;
+    set_capacity(drops: number, splashes: number, streams: number): void;
+    capacity(): Uint32Array;
+    dropped_spawns(): Uint32Array;
+    reset_dropped_spawns(): void;
     free(): void;
 };
 
+// Offsets into stats() (mirrors Stats in sim/stats.rs)
+export const STATS_KINDS = ["drops", "splashes", "spray", "streams", "ripples", "flakes", "hail"] as const;
+export const STATS_CAUSES = ["ground", "surface", "stream_fall", "pool", "other"] as const;
+export const STATS_LAYOUT = {
+    live: 0,
+    spawned: 7,
+    died: 14,
+    refused: 21,
+    causes: 28,
+    impacts: 33,   // 256 segment classes
+    phases: 289,   // one per stats_phase_names()
+} as const;
+
+// Impact event records (mirrors Impact in sim/impact.rs): five f32 - x, y,
+// z, nx, ny - then segment class (255 = off the scene) and kind (index into
+// STATS_CAUSES), padded to 24 bytes
+export const IMPACT_STRIDE = 24;
+export const IMPACT_NO_CLASS = 255;
+
+export type Impacts = {
+    count: number;
+    floats: Float32Array;  // IMPACT_STRIDE / 4 per event
+    bytes: Uint8Array;     // class at i * IMPACT_STRIDE + 20, kind at + 21
+};
+
 let wasmMemory: WebAssembly.Memory | null = null;
 let RainWorldClass: (new (width: number, height: number) => RainWorldInstance) | null = null;
 
@@ -33,4 +105,17 @@ export function getMemory(): WebAssembly.Memory | null {
     return wasmMemory;
 }
 
+/** Views over the last frame's impact events, in wasm memory (no copy).
+ *  Valid until the next tick; read them before ticking again. */
+export function readImpacts(world: RainWorldInstance): Impacts | null {
+    if (!wasmMemory) return null;
+    const count = world.impact_count();
+    const ptr = world.impact_ptr();
+    return {
+        count,
+        floats: new Float32Array(wasmMemory.buffer, ptr, count * IMPACT_STRIDE / 4),
+        bytes: new Uint8Array(wasmMemory.buffer, ptr, count * IMPACT_STRIDE),
+    };
+}
+
 export type RainWorld = RainWorldInstance;